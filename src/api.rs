@@ -11,6 +11,7 @@ mod palettes;
 mod plugins;
 mod rate_limit;
 mod reports;
+mod revisions;
 mod search;
 mod settings;
 mod tags;
@@ -21,14 +22,15 @@ mod user_actions;
 mod users;
 
 pub use api_keys::{ApiKeySummary, CreatedApiKey};
-pub use client::{DiscourseClient, VersionInfo};
+pub use client::{DiscourseClient, ResourceAccess, VersionInfo};
 pub use models::*;
 pub use groups::AddMembersOutcome;
 pub use invites::InviteResult;
 pub use reports::{AdminReport, ReportPoint};
-pub use search::SearchHit;
+pub use revisions::PostRevision;
+pub use search::{SearchHit, SearchPost};
 pub use tags::TagInfo;
-pub use topics::{PmTopicSummary, PostInfo};
-pub use uploads::UploadInfo;
+pub use topics::{BulkOperation, PmTopicSummary, PostInfo, TopicTimer, TopicUpdate};
+pub use uploads::{UploadInfo, UploadLookup};
 pub use user_actions::UserAction;
-pub use users::{UserDetail, UserSummary};
+pub use users::{DeleteUserOptions, UserDetail, UserSummary};
@@ -1,15 +1,58 @@
 use super::client::DiscourseClient;
 use super::error::http_error;
-use super::models::{CategoriesResponse, CategoryInfo, CategoryResponse, CreateCategoryResponse};
+use super::models::{
+    CategoriesResponse, CategoryDetailResponse, CategoryInfo, CategoryResponse,
+    CreateCategoryResponse,
+};
 use anyhow::{Context, Result, anyhow};
 use reqwest::StatusCode;
-use serde_json::Value;
-use std::collections::HashMap;
+use serde_json::{Map, Value, json};
+use std::collections::{HashMap, HashSet};
 
 impl DiscourseClient {
-    /// Fetch a category by ID (topics list included).
-    pub fn fetch_category(&self, category_id: u64) -> Result<CategoryResponse> {
-        let path = format!("/c/{}.json", category_id);
+    /// Fetch a category by ID with every topic in it, following
+    /// `more_topics_url` page by page. `include_subcategories` also lists
+    /// topics from its subcategories.
+    pub fn fetch_category(
+        &self,
+        category_id: u64,
+        include_subcategories: bool,
+    ) -> Result<CategoryResponse> {
+        let mut first: Option<CategoryResponse> = None;
+        let mut seen = HashSet::new();
+        let mut page = 0;
+        loop {
+            let body = self.fetch_category_page(category_id, include_subcategories, page)?;
+            let more = body.topic_list.more_topics_url.is_some();
+            let new_topics: Vec<_> = body
+                .topic_list
+                .topics
+                .iter()
+                .filter(|topic| seen.insert(topic.id))
+                .cloned()
+                .collect();
+            let done = !more || new_topics.is_empty();
+            match first.as_mut() {
+                None => first = Some(body),
+                Some(first) => first.topic_list.topics.extend(new_topics),
+            }
+            if done {
+                break;
+            }
+            page += 1;
+        }
+        let mut body = first.ok_or_else(|| anyhow!("category not found: {}", category_id))?;
+        body.topic_list.more_topics_url = None;
+        Ok(body)
+    }
+
+    fn fetch_category_page(
+        &self,
+        category_id: u64,
+        include_subcategories: bool,
+        page: u32,
+    ) -> Result<CategoryResponse> {
+        let path = category_page_path(category_id, include_subcategories, page);
         let response = self.get(&path)?;
         let status = response.status();
         let text = response.text().context("reading category response body")?;
@@ -24,6 +67,23 @@ impl DiscourseClient {
         Ok(body)
     }
 
+    /// Fetch one category's full settings, including group permissions.
+    pub fn fetch_category_detail(&self, category_id: u64) -> Result<CategoryInfo> {
+        let path = format!("/c/{}/show.json", category_id);
+        let response = self.get(&path)?;
+        let status = response.status();
+        let text = response.text().context("reading category response body")?;
+        if !status.is_success() {
+            if status == StatusCode::NOT_FOUND {
+                return Err(anyhow!("category not found: {}", category_id));
+            }
+            return Err(http_error("category request", status, &text));
+        }
+        let body: CategoryDetailResponse =
+            serde_json::from_str(&text).context("reading category json")?;
+        Ok(body.category)
+    }
+
     /// Fetch all categories.
     pub fn fetch_categories(&self) -> Result<Vec<CategoryInfo>> {
         let response = self.get("/categories.json?include_subcategories=true")?;
@@ -55,19 +115,12 @@ impl DiscourseClient {
         Ok(categories)
     }
 
-    /// Create a category with basic fields copied from a source category.
+    /// Create a category from `category`'s settings, under
+    /// `parent_category_id` when set. Empty `group_permissions` leaves the
+    /// category open to everyone.
     pub fn create_category(&self, category: &CategoryInfo) -> Result<u64> {
-        let mut payload = vec![("name", category.name.clone())];
-        if !category.slug.is_empty() {
-            payload.push(("slug", category.slug.clone()));
-        }
-        if let Some(color) = category.color.clone() {
-            payload.push(("color", color));
-        }
-        if let Some(text_color) = category.text_color.clone() {
-            payload.push(("text_color", text_color));
-        }
-        let response = self.send_retrying(|| Ok(self.post("/categories")?.form(&payload)))?;
+        let payload = category_payload(category);
+        let response = self.send_retrying(|| Ok(self.post("/categories")?.json(&payload)))?;
         let status = response.status();
         let text = response.text().context("reading category response body")?;
         if !status.is_success() {
@@ -78,6 +131,57 @@ impl DiscourseClient {
         Ok(body.category.id)
     }
 
+    /// Replace a category's settings with `category`'s. Empty
+    /// `group_permissions` opens it to everyone; empty tag lists clear the
+    /// restrictions.
+    pub fn update_category(&self, category_id: u64, category: &CategoryInfo) -> Result<()> {
+        let path = format!("/categories/{}.json", category_id);
+        let payload = category_payload(category);
+        let response = self.send_retrying(|| Ok(self.put(&path)?.json(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("update category request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Delete a category. Discourse refuses while it still has topics or
+    /// subcategories.
+    pub fn delete_category(&self, category_id: u64) -> Result<()> {
+        let path = format!("/categories/{}.json", category_id);
+        let response = self.send_retrying(|| self.delete_builder(&path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("delete category request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Set the sort position of each `(category_id, position)` in one request.
+    pub fn reorder_categories(&self, positions: &[(u64, i64)]) -> Result<()> {
+        let mapping: Map<String, Value> = positions
+            .iter()
+            .map(|(id, position)| (id.to_string(), json!(position)))
+            .collect();
+        let payload = [("mapping", Value::Object(mapping).to_string())];
+        let response =
+            self.send_retrying(|| Ok(self.post("/categories/reorder")?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("reorder categories request", status, &text));
+        }
+        Ok(())
+    }
+
     fn fetch_site_categories(&self) -> Result<Vec<CategoryInfo>> {
         let response = self.get("/site.json")?;
         let status = response.status();
@@ -105,3 +209,69 @@ impl DiscourseClient {
         Ok(categories)
     }
 }
+
+/// JSON body for creating or updating a category.
+fn category_payload(category: &CategoryInfo) -> Value {
+    let mut permissions: Map<String, Value> = category
+        .group_permissions
+        .iter()
+        .map(|p| (p.group_name.clone(), json!(p.permission_type)))
+        .collect();
+    if permissions.is_empty() {
+        permissions.insert("everyone".to_string(), json!(1));
+    }
+    let mut payload = json!({
+        "name": category.name,
+        "parent_category_id": category.parent_category_id,
+        "allowed_tags": category.allowed_tags,
+        "allowed_tag_groups": category.allowed_tag_groups,
+        "required_tag_groups": category.required_tag_groups,
+        "permissions": permissions,
+    });
+    if !category.slug.is_empty() {
+        payload["slug"] = json!(category.slug);
+    }
+    if let Some(color) = &category.color {
+        payload["color"] = json!(color);
+    }
+    if let Some(text_color) = &category.text_color {
+        payload["text_color"] = json!(text_color);
+    }
+    if let Some(template) = &category.topic_template {
+        payload["topic_template"] = json!(template);
+    }
+    if let Some(view) = &category.default_view {
+        payload["default_view"] = json!(view);
+    }
+    if let Some(position) = category.position {
+        payload["position"] = json!(position);
+    }
+    if let Some(minimum) = category.minimum_required_tags {
+        payload["minimum_required_tags"] = json!(minimum);
+    }
+    payload
+}
+
+/// Discourse lists subcategory topics unless `no_subcategories` is present,
+/// so the flag is only sent to leave them out.
+fn category_page_path(category_id: u64, include_subcategories: bool, page: u32) -> String {
+    let mut path = format!("/c/{}.json?page={}", category_id, page);
+    if !include_subcategories {
+        path.push_str("&no_subcategories=true");
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::category_page_path;
+
+    #[test]
+    fn category_page_path_only_sends_no_subcategories_to_exclude_them() {
+        assert_eq!(category_page_path(4, true, 0), "/c/4.json?page=0");
+        assert_eq!(
+            category_page_path(4, false, 2),
+            "/c/4.json?page=2&no_subcategories=true"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use super::error::http_error;
 use super::models::{AboutResponse, SiteResponse};
 use super::rate_limit::{
     RETRY_BUFFER, parse_rate_limit_wait, summarize_rate_limit_body,
@@ -11,6 +12,15 @@ use reqwest::header::{HeaderMap, HeaderValue};
 
 const MAX_RATE_LIMIT_RETRIES: u32 = 5;
 
+/// Whether a forum resource (topic, post, user) is there for the API user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceAccess {
+    Visible,
+    /// It exists, but the API user isn't allowed to see it (HTTP 403).
+    Forbidden,
+    NotFound,
+}
+
 #[derive(Debug, Clone)]
 pub struct VersionInfo {
     pub version: Option<String>,
@@ -71,6 +81,23 @@ impl DiscourseClient {
         self.send_retrying(|| Ok(self.client.get(&url)))
     }
 
+    /// GET an absolute or protocol-relative URL, such as an upload on a CDN.
+    /// API credentials are only sent when the URL is on this Discourse.
+    pub(crate) fn get_url(&self, url: &str) -> Result<Response> {
+        let url = if url.starts_with("//") {
+            format!("https:{}", url)
+        } else if url.starts_with('/') {
+            format!("{}{}", self.baseurl, url)
+        } else {
+            url.to_string()
+        };
+        if url.starts_with(&format!("{}/", self.baseurl)) {
+            return self.send_retrying(|| Ok(self.client.get(&url)));
+        }
+        let anonymous = Client::new();
+        self.send_retrying(|| Ok(anonymous.get(&url)))
+    }
+
     pub(crate) fn post(&self, path: &str) -> Result<reqwest::blocking::RequestBuilder> {
         let url = format!("{}{}", self.baseurl, path);
         Ok(self.client.post(url))
@@ -91,6 +118,31 @@ impl DiscourseClient {
         Ok(self.client.delete(url))
     }
 
+    /// GET `path` and report whether it exists: `false` only on 404. A 403
+    /// means it exists but the API user can't see it.
+    pub(crate) fn resource_exists(&self, path: &str, what: &str) -> Result<bool> {
+        Ok(self.resource_access(path, what)? != ResourceAccess::NotFound)
+    }
+
+    /// GET `path` and report whether the API user can see it, an error on
+    /// any failure other than 403 or 404.
+    pub(crate) fn resource_access(&self, path: &str, what: &str) -> Result<ResourceAccess> {
+        let response = self.get(path)?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(ResourceAccess::Visible);
+        }
+        match status {
+            StatusCode::FORBIDDEN => return Ok(ResourceAccess::Forbidden),
+            StatusCode::NOT_FOUND => return Ok(ResourceAccess::NotFound),
+            _ => {}
+        }
+        let text = response
+            .text()
+            .unwrap_or_else(|_| "<failed to read response body>".to_string());
+        Err(http_error(what, status, &text))
+    }
+
     /// Send a request, retrying up to 5 times on HTTP 429 responses.
     ///
     /// The `build` closure is called once per attempt and must produce a fresh
@@ -9,6 +9,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashSet;
 
+/// Members per page when listing a group; Discourse caps this at 1000.
+const MEMBERS_PAGE_SIZE: usize = 1000;
+
 /// Result of a bulk add-members call.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AddMembersOutcome {
@@ -46,19 +49,29 @@ impl DiscourseClient {
         Err(anyhow!("group not found: {}", group_id))
     }
 
+    /// Fetch every member of a group, page by page.
     pub fn fetch_group_members(
         &self,
         group_id: u64,
         group_name: Option<&str>,
     ) -> Result<Vec<GroupMember>> {
+        Ok(self.fetch_group_membership(group_id, group_name)?.members)
+    }
+
+    /// Fetch every member of a group along with its owners.
+    pub fn fetch_group_membership(
+        &self,
+        group_id: u64,
+        group_name: Option<&str>,
+    ) -> Result<GroupMembersResponse> {
         let id_path = format!("/groups/{}/members.json", group_id);
-        if let Some(members) = self.fetch_group_members_by_path(&id_path)? {
-            return Ok(members);
+        if let Some(membership) = self.fetch_group_members_by_path(&id_path)? {
+            return Ok(membership);
         }
         if let Some(name) = group_name {
             let name_path = format!("/groups/{}/members.json", name);
-            if let Some(members) = self.fetch_group_members_by_path(&name_path)? {
-                return Ok(members);
+            if let Some(membership) = self.fetch_group_members_by_path(&name_path)? {
+                return Ok(membership);
             }
         }
         Err(anyhow!("group not found: {}", group_id))
@@ -66,142 +79,7 @@ impl DiscourseClient {
 
     /// Create a group with detailed settings copied from a source group.
     pub fn create_group(&self, group: &GroupDetail) -> Result<u64> {
-        let mut payload: Vec<(String, String)> = Vec::new();
-        payload.push(("group[name]".to_string(), group.name.clone()));
-        if let Some(full_name) = group.full_name.clone() {
-            payload.push(("group[full_name]".to_string(), full_name));
-        }
-        push_opt(&mut payload, "group[title]", group.title.as_deref());
-        push_opt(
-            &mut payload,
-            "group[grant_trust_level]",
-            group
-                .grant_trust_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[visibility_level]",
-            group
-                .visibility_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[mentionable_level]",
-            group
-                .mentionable_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[messageable_level]",
-            group
-                .messageable_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[default_notification_level]",
-            group
-                .default_notification_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[members_visibility_level]",
-            group
-                .members_visibility_level
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[primary_group]",
-            group
-                .primary_group
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[public_admission]",
-            group
-                .public_admission
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[public_exit]",
-            group.public_exit.as_ref().map(|v| v.to_string()).as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[allow_membership_requests]",
-            group
-                .allow_membership_requests
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[automatic_membership_email_domains]",
-            group.automatic_membership_email_domains.as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[automatic_membership_retroactive]",
-            group
-                .automatic_membership_retroactive
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[membership_request_template]",
-            group.membership_request_template.as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[flair_icon]",
-            group.flair_icon.as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[flair_upload_id]",
-            group
-                .flair_upload_id
-                .as_ref()
-                .map(|v| v.to_string())
-                .as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[flair_color]",
-            group.flair_color.as_deref(),
-        );
-        push_opt(
-            &mut payload,
-            "group[flair_background_color]",
-            group.flair_background_color.as_deref(),
-        );
-        push_opt(&mut payload, "group[bio_raw]", group.bio_raw.as_deref());
+        let payload = group_payload(group);
         let response = self.send_retrying(|| Ok(self.post("/admin/groups")?.form(&payload)))?;
         let status = response.status();
         let text = response.text().context("reading group response body")?;
@@ -224,6 +102,35 @@ impl DiscourseClient {
         Ok(id)
     }
 
+    /// Update a group's settings. Fields left as `None` are not sent.
+    pub fn update_group(&self, group_id: u64, group: &GroupDetail) -> Result<()> {
+        let path = format!("/groups/{}.json", group_id);
+        let payload = group_payload(group);
+        let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("update group request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Delete a group.
+    pub fn delete_group(&self, group_id: u64) -> Result<()> {
+        let path = format!("/admin/groups/{}.json", group_id);
+        let response = self.send_retrying(|| self.delete_builder(&path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("delete group request", status, &text));
+        }
+        Ok(())
+    }
+
     /// Add members to a group by username (PUT /groups/:id/members.json).
     pub fn add_group_members_by_username(
         &self,
@@ -272,6 +179,39 @@ impl DiscourseClient {
         Ok(())
     }
 
+    /// Make existing or new members owners of a group, by username.
+    pub fn add_group_owners(&self, group_id: u64, usernames: &[String]) -> Result<()> {
+        if usernames.is_empty() {
+            return Ok(());
+        }
+        let path = format!("/groups/{}/owners.json", group_id);
+        let joined = usernames.join(",");
+        let payload = [("usernames", joined.as_str())];
+        let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("add group owners request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Demote a group owner to a plain member.
+    pub fn remove_group_owner(&self, group_id: u64, user_id: u64) -> Result<()> {
+        let path = format!("/admin/groups/{}/owners.json?user_id={}", group_id, user_id);
+        let response = self.send_retrying(|| self.delete_builder(&path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("remove group owner request", status, &text));
+        }
+        Ok(())
+    }
+
     /// Return the list of groups a user belongs to, by username.
     pub fn fetch_user_groups(&self, username: &str) -> Result<Vec<GroupSummary>> {
         let path = format!("/u/{}.json", username);
@@ -337,19 +277,43 @@ impl DiscourseClient {
         Ok(Some(body.group))
     }
 
-    fn fetch_group_members_by_path(&self, path: &str) -> Result<Option<Vec<GroupMember>>> {
-        let response = self.get(path)?;
-        let status = response.status();
-        let text = response.text().context("reading group members body")?;
-        if !status.is_success() {
-            if status == StatusCode::NOT_FOUND {
-                return Ok(None);
+    fn fetch_group_members_by_path(&self, path: &str) -> Result<Option<GroupMembersResponse>> {
+        let mut membership: Option<GroupMembersResponse> = None;
+        let mut seen = HashSet::new();
+        loop {
+            let offset = seen.len();
+            let page_path = format!("{}?limit={}&offset={}", path, MEMBERS_PAGE_SIZE, offset);
+            let response = self.get(&page_path)?;
+            let status = response.status();
+            let text = response.text().context("reading group members body")?;
+            if !status.is_success() {
+                if status == StatusCode::NOT_FOUND && membership.is_none() {
+                    return Ok(None);
+                }
+                return Err(http_error("group members request", status, &text));
+            }
+            let page: GroupMembersResponse =
+                serde_json::from_str(&text).context("parsing group members json")?;
+            let full_page = page.members.len() >= MEMBERS_PAGE_SIZE;
+            let new_members: Vec<GroupMember> = page
+                .members
+                .into_iter()
+                .filter(|member| seen.insert(member.id))
+                .collect();
+            let done = !full_page || new_members.is_empty();
+            match membership.as_mut() {
+                None => {
+                    membership = Some(GroupMembersResponse {
+                        members: new_members,
+                        owners: page.owners,
+                    })
+                }
+                Some(membership) => membership.members.extend(new_members),
+            }
+            if done {
+                return Ok(membership);
             }
-            return Err(http_error("group members request", status, &text));
         }
-        let body: GroupMembersResponse =
-            serde_json::from_str(&text).context("parsing group members json")?;
-        Ok(Some(body.members))
     }
 
     fn fetch_groups_admin(&self) -> Result<Option<Vec<GroupSummary>>> {
@@ -422,6 +386,147 @@ impl DiscourseClient {
     }
 }
 
+/// Form fields for creating or updating a group; `None` fields are left out.
+fn group_payload(group: &GroupDetail) -> Vec<(String, String)> {
+    let mut payload: Vec<(String, String)> = Vec::new();
+    payload.push(("group[name]".to_string(), group.name.clone()));
+    if let Some(full_name) = group.full_name.clone() {
+        payload.push(("group[full_name]".to_string(), full_name));
+    }
+    push_opt(&mut payload, "group[title]", group.title.as_deref());
+    push_opt(
+        &mut payload,
+        "group[grant_trust_level]",
+        group
+            .grant_trust_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[visibility_level]",
+        group
+            .visibility_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[mentionable_level]",
+        group
+            .mentionable_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[messageable_level]",
+        group
+            .messageable_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[default_notification_level]",
+        group
+            .default_notification_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[members_visibility_level]",
+        group
+            .members_visibility_level
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[primary_group]",
+        group
+            .primary_group
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[public_admission]",
+        group
+            .public_admission
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[public_exit]",
+        group.public_exit.as_ref().map(|v| v.to_string()).as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[allow_membership_requests]",
+        group
+            .allow_membership_requests
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[automatic_membership_email_domains]",
+        group.automatic_membership_email_domains.as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[automatic_membership_retroactive]",
+        group
+            .automatic_membership_retroactive
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[membership_request_template]",
+        group.membership_request_template.as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[flair_icon]",
+        group.flair_icon.as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[flair_upload_id]",
+        group
+            .flair_upload_id
+            .as_ref()
+            .map(|v| v.to_string())
+            .as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[flair_color]",
+        group.flair_color.as_deref(),
+    );
+    push_opt(
+        &mut payload,
+        "group[flair_background_color]",
+        group.flair_background_color.as_deref(),
+    );
+    push_opt(&mut payload, "group[bio_raw]", group.bio_raw.as_deref());
+    payload
+}
+
 fn push_opt(payload: &mut Vec<(String, String)>, key: &str, value: Option<&str>) {
     if let Some(value) = value {
         payload.push((key.to_string(), value.to_string()));
@@ -1,4 +1,6 @@
+use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// Response payload for site.json.
 #[derive(Debug, Deserialize)]
@@ -28,29 +30,64 @@ pub struct AboutInfo {
 /// Response payload for topic JSON.
 #[derive(Debug, Deserialize)]
 pub struct TopicResponse {
+    pub id: u64,
     #[serde(default)]
     pub title: Option<String>,
     #[serde(default)]
     pub slug: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_tag_names")]
+    pub tags: Vec<String>,
     pub post_stream: PostStream,
 }
 
+/// Accept either plain tag names or tag objects carrying a `name` field.
+fn deserialize_tag_names<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(values
+        .iter()
+        .filter_map(|v| {
+            v.as_str()
+                .or_else(|| v.get("name").and_then(|n| n.as_str()))
+                .map(|s| s.to_string())
+        })
+        .collect())
+}
+
 /// Topic post stream.
 #[derive(Debug, Deserialize)]
 pub struct PostStream {
     pub posts: Vec<Post>,
+    /// Every post ID in the topic, in order; `posts` only holds the first
+    /// chunk.
+    #[serde(default)]
+    pub stream: Vec<u64>,
 }
 
 /// Topic post.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct Post {
     pub id: u64,
     #[serde(default)]
+    pub post_number: Option<u64>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
     pub raw: Option<String>,
     #[serde(default)]
     pub updated_at: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
+    /// Revision number; starts at 1 and bumps with each edit.
+    #[serde(default)]
+    pub version: Option<u64>,
+    /// 1 regular, 2 moderator action, 3 small action, 4 whisper.
+    #[serde(default)]
+    pub post_type: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -68,7 +105,7 @@ pub struct CategoryResponse {
 }
 
 /// Category metadata.
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct CategoryInfo {
     pub name: String,
     pub slug: String,
@@ -81,6 +118,59 @@ pub struct CategoryInfo {
     pub subcategory_list: Vec<CategoryInfo>,
     #[serde(default)]
     pub parent_category_id: Option<u64>,
+    #[serde(default)]
+    pub topic_template: Option<String>,
+    /// Default topic list: `latest`, `top`, ...
+    #[serde(default)]
+    pub default_view: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_tag_names")]
+    pub allowed_tags: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_tag_names")]
+    pub allowed_tag_groups: Vec<String>,
+    /// Only returned by /c/{id}/show.json; empty means open to everyone.
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub group_permissions: Vec<CategoryPermission>,
+    /// Path of the category's "About" topic, whose first post is the
+    /// description.
+    #[serde(default)]
+    pub topic_url: Option<String>,
+    /// Sort position; only used when `fixed_category_positions` is on.
+    #[serde(default)]
+    pub position: Option<i64>,
+    #[serde(default)]
+    pub minimum_required_tags: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub required_tag_groups: Vec<RequiredTagGroup>,
+}
+
+/// A tag group topics in a category must use at least `min_count` tags from.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct RequiredTagGroup {
+    pub name: String,
+    pub min_count: u64,
+}
+
+/// One group's access to a category.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CategoryPermission {
+    pub group_name: String,
+    /// 1 create/reply/see, 2 reply/see, 3 see.
+    pub permission_type: u64,
+}
+
+/// Treat an explicit `null` like a missing field.
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Response payload for /c/{id}/show.json.
+#[derive(Debug, Deserialize)]
+pub struct CategoryDetailResponse {
+    pub category: CategoryInfo,
 }
 
 /// Response payload for categories.json.
@@ -99,14 +189,19 @@ pub struct CategoryList {
 #[derive(Debug, Deserialize)]
 pub struct TopicList {
     pub topics: Vec<TopicSummary>,
+    /// Set while further pages of topics remain.
+    #[serde(default)]
+    pub more_topics_url: Option<String>,
 }
 
 /// Topic summary.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TopicSummary {
     pub id: u64,
     pub title: String,
     pub slug: String,
+    #[serde(default)]
+    pub category_id: Option<u64>,
 }
 
 /// Group summary.
@@ -116,6 +211,9 @@ pub struct GroupSummary {
     pub name: String,
     #[serde(default)]
     pub full_name: Option<String>,
+    /// Built-in groups Discourse manages itself (staff, trust levels, ...).
+    #[serde(default)]
+    pub automatic: bool,
 }
 
 /// Response payload for groups.json.
@@ -124,7 +222,7 @@ pub struct GroupsResponse {
     pub groups: Vec<GroupSummary>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct GroupMember {
     pub id: u64,
     pub username: String,
@@ -135,6 +233,8 @@ pub struct GroupMember {
 #[derive(Debug, Deserialize)]
 pub struct GroupMembersResponse {
     pub members: Vec<GroupMember>,
+    #[serde(default)]
+    pub owners: Vec<GroupMember>,
 }
 
 /// Response payload for group detail.
@@ -144,7 +244,7 @@ pub struct GroupDetailResponse {
 }
 
 /// Group details with settings used for deep-copy.
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
 pub struct GroupDetail {
     pub id: u64,
     pub name: String,
@@ -0,0 +1,127 @@
+use super::client::DiscourseClient;
+use super::error::http_error;
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One edit of a post. Revision numbers start at 2; revision 1 is the
+/// post as first written.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PostRevision {
+    pub post_id: u64,
+    pub current_revision: u64,
+    #[serde(default)]
+    pub last_revision: Option<u64>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub edit_reason: Option<String>,
+}
+
+impl DiscourseClient {
+    /// Fetch one revision of a post.
+    pub fn fetch_post_revision(&self, post_id: u64, revision: u64) -> Result<PostRevision> {
+        let path = format!("/posts/{}/revisions/{}.json", post_id, revision);
+        let response = self.get(&path)?;
+        let status = response.status();
+        let text = response.text().context("reading revision response body")?;
+        if !status.is_success() {
+            return Err(http_error("post revision request", status, &text));
+        }
+        serde_json::from_str(&text).context("parsing post revision json")
+    }
+
+    /// Fetch a post's latest revision, or `None` if it was never edited.
+    pub fn fetch_latest_post_revision(&self, post_id: u64) -> Result<Option<PostRevision>> {
+        let path = format!("/posts/{}/revisions/latest.json", post_id);
+        let response = self.get(&path)?;
+        let status = response.status();
+        let text = response.text().context("reading revision response body")?;
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(http_error("post revision request", status, &text));
+        }
+        let revision = serde_json::from_str(&text).context("parsing post revision json")?;
+        Ok(Some(revision))
+    }
+
+    /// Fetch a post's raw Markdown as it was after `revision`.
+    pub fn fetch_post_raw_at_revision(
+        &self,
+        topic_id: u64,
+        post_number: u64,
+        revision: u64,
+    ) -> Result<String> {
+        let path = raw_revision_path(topic_id, post_number, revision);
+        let response = self.get(&path)?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading raw revision response body")?;
+        if !status.is_success() {
+            return Err(http_error("raw revision request", status, &text));
+        }
+        Ok(text)
+    }
+
+    /// Fetch a post's raw Markdown as first written (revision 1): the body
+    /// before the first of revisions `2..=latest` that changed it, or `None`
+    /// if none did. `/raw` has nothing to serve for revision 1.
+    pub fn fetch_post_original_raw(&self, post_id: u64, latest: u64) -> Result<Option<String>> {
+        for revision in 2..=latest {
+            let path = format!("/posts/{}/revisions/{}.json", post_id, revision);
+            let response = self.get(&path)?;
+            let status = response.status();
+            let text = response.text().context("reading revision response body")?;
+            if !status.is_success() {
+                return Err(http_error("post revision request", status, &text));
+            }
+            let value: Value = serde_json::from_str(&text).context("parsing post revision json")?;
+            if let Some(raw) = raw_before_revision(&value) {
+                return Ok(Some(raw));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// The body a revision replaced, from its `modifications.raw` pair of
+/// `[before, after]`; `None` when the revision left the body alone.
+fn raw_before_revision(revision: &Value) -> Option<String> {
+    revision
+        .get("modifications")?
+        .get("raw")?
+        .get(0)?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// `/raw` serves the body as it was after `revision`, i.e. the result of
+/// that edit.
+fn raw_revision_path(topic_id: u64, post_number: u64, revision: u64) -> String {
+    format!("/raw/{}/{}?revision={}", topic_id, post_number, revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{raw_before_revision, raw_revision_path};
+    use serde_json::json;
+
+    #[test]
+    fn raw_revision_path_asks_for_the_body_after_the_revision() {
+        assert_eq!(raw_revision_path(12, 3, 5), "/raw/12/3?revision=5");
+    }
+
+    #[test]
+    fn raw_before_revision_reads_the_replaced_body() {
+        let edit = json!({"modifications": {"raw": ["first draft", "second draft"]}});
+        assert_eq!(raw_before_revision(&edit).as_deref(), Some("first draft"));
+        let retitle = json!({"modifications": {"title": ["Old", "New"]}});
+        assert_eq!(raw_before_revision(&retitle), None);
+    }
+}
@@ -2,6 +2,7 @@ use super::client::DiscourseClient;
 use super::error::http_error;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 /// One result row in a search response — distilled from the topic stanza of
 /// `/search.json` (which contains far more than we need).
@@ -19,10 +20,31 @@ pub struct SearchHit {
     pub tags: Option<Vec<String>>,
 }
 
+/// A post in a search response.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SearchPost {
+    pub id: u64,
+    pub topic_id: u64,
+    #[serde(default)]
+    pub post_number: Option<u64>,
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct RawSearchResponse {
     #[serde(default)]
     topics: Vec<SearchHit>,
+    #[serde(default)]
+    posts: Vec<SearchPost>,
+    #[serde(default)]
+    grouped_search_result: Option<GroupedSearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupedSearchResult {
+    #[serde(default)]
+    more_full_page_results: Option<bool>,
 }
 
 impl DiscourseClient {
@@ -33,21 +55,78 @@ impl DiscourseClient {
             "/search.json?q={}",
             urlencode_form(query)
         );
-        let response = self.get(&path)?;
+        Ok(self.search_page(&path)?.topics)
+    }
+
+    /// Search for topics, following result pages until Discourse reports
+    /// no more, so every match is returned rather than just the first page.
+    pub fn search_all_topics(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let mut seen = HashSet::new();
+        let mut hits = Vec::new();
+        for page in self.search_all_pages(query)? {
+            hits.extend(page.topics.into_iter().filter(|hit| seen.insert(hit.id)));
+        }
+        Ok(hits)
+    }
+
+    /// Every post matching `query`, across all result pages.
+    pub fn search_all_posts(&self, query: &str) -> Result<Vec<SearchPost>> {
+        let mut seen = HashSet::new();
+        let mut posts = Vec::new();
+        for page in self.search_all_pages(query)? {
+            posts.extend(page.posts.into_iter().filter(|post| seen.insert(post.id)));
+        }
+        Ok(posts)
+    }
+
+    fn search_all_pages(&self, query: &str) -> Result<Vec<RawSearchResponse>> {
+        let mut seen = HashSet::new();
+        let mut pages = Vec::new();
+        let mut page = 1;
+        loop {
+            let path = format!(
+                "/search.json?q={}&page={}",
+                urlencode_form(query),
+                page
+            );
+            let body = self.search_page(&path)?;
+            let more = body
+                .grouped_search_result
+                .as_ref()
+                .and_then(|group| group.more_full_page_results)
+                .unwrap_or(false);
+            // Stop if a page brings nothing new, in case Discourse keeps
+            // reporting more results.
+            let fresh = body
+                .posts
+                .iter()
+                .map(|post| ("post", post.id))
+                .chain(body.topics.iter().map(|topic| ("topic", topic.id)))
+                .filter(|key| seen.insert(*key))
+                .count()
+                > 0;
+            pages.push(body);
+            if !more || !fresh {
+                return Ok(pages);
+            }
+            page += 1;
+        }
+    }
+
+    fn search_page(&self, path: &str) -> Result<RawSearchResponse> {
+        let response = self.get(path)?;
         let status = response.status();
         let text = response.text().context("reading search response body")?;
         if !status.is_success() {
             return Err(http_error("search request", status, &text));
         }
-        let body: RawSearchResponse =
-            serde_json::from_str(&text).context("parsing search response json")?;
-        Ok(body.topics)
+        serde_json::from_str(&text).context("parsing search response json")
     }
 }
 
 /// Minimal `application/x-www-form-urlencoded` encoder for the query string.
 /// Avoids pulling in an extra crate just for one field.
-fn urlencode_form(input: &str) -> String {
+pub(super) fn urlencode_form(input: &str) -> String {
     let mut out = String::with_capacity(input.len());
     for byte in input.as_bytes() {
         let b = *byte;
@@ -64,7 +143,7 @@ fn urlencode_form(input: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::urlencode_form;
+    use super::{RawSearchResponse, urlencode_form};
 
     #[test]
     fn encodes_spaces_as_plus() {
@@ -94,4 +173,20 @@ mod tests {
             "hello+category%3Afoo+%40bob"
         );
     }
+
+    #[test]
+    fn reads_whether_more_pages_follow() {
+        let body: RawSearchResponse = serde_json::from_str(
+            r#"{"topics":[{"id":7,"title":"Old"}],"grouped_search_result":{"more_full_page_results":true}}"#,
+        )
+        .unwrap();
+        assert_eq!(body.topics[0].id, 7);
+        assert_eq!(
+            body.grouped_search_result
+                .and_then(|group| group.more_full_page_results),
+            Some(true)
+        );
+        let last: RawSearchResponse = serde_json::from_str(r#"{"topics":[]}"#).unwrap();
+        assert!(last.grouped_search_result.is_none());
+    }
 }
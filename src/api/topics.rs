@@ -1,9 +1,12 @@
-use super::client::DiscourseClient;
+use super::client::{DiscourseClient, ResourceAccess};
 use super::error::http_error;
-use super::models::{CreatePostResponse, TopicResponse};
+use super::models::{CreatePostResponse, Post, TopicList, TopicResponse, TopicSummary};
 use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
+use reqwest::blocking::RequestBuilder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PostInfo {
@@ -13,6 +16,8 @@ pub struct PostInfo {
     pub post_number: Option<u64>,
     #[serde(default)]
     pub raw: Option<String>,
+    #[serde(default)]
+    pub version: Option<u64>,
 }
 
 /// Distilled row from /topics/private-messages-*.json.
@@ -33,6 +38,124 @@ pub struct PmTopicSummary {
     pub unread: Option<u64>,
 }
 
+#[derive(Debug, Deserialize)]
+struct LatestResponse {
+    topic_list: TopicList,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicPostsResponse {
+    post_stream: TopicPostsStream,
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicPostsStream {
+    posts: Vec<Post>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BulkTopicsResponse {
+    #[serde(default)]
+    topic_ids: Vec<u64>,
+}
+
+/// Topic-level fields to change with `update_topic`. `None` leaves the field
+/// as it is on the server.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TopicUpdate {
+    pub title: Option<String>,
+    pub category_id: Option<u64>,
+    pub tags: Option<Vec<String>>,
+    /// Featured link URL; an empty string removes it.
+    pub featured_link: Option<String>,
+}
+
+impl TopicUpdate {
+    /// True when no field would change.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.category_id.is_none()
+            && self.tags.is_none()
+            && self.featured_link.is_none()
+    }
+
+    fn payload(&self) -> Vec<(&'static str, String)> {
+        let mut payload = Vec::new();
+        if let Some(title) = &self.title {
+            payload.push(("title", title.clone()));
+        }
+        if let Some(category_id) = self.category_id {
+            payload.push(("category_id", category_id.to_string()));
+        }
+        if let Some(tags) = &self.tags {
+            if tags.is_empty() {
+                // An empty `tags[]` clears every tag.
+                payload.push(("tags[]", String::new()));
+            }
+            for tag in tags {
+                payload.push(("tags[]", tag.clone()));
+            }
+        }
+        if let Some(featured_link) = &self.featured_link {
+            payload.push(("featured_link", featured_link.clone()));
+        }
+        payload
+    }
+}
+
+/// A topic timer: what happens (`status_type`, e.g. `close`, `open`,
+/// `delete` or `publish_to_category`) and when (`time`, in hours from now
+/// or as a timestamp).
+#[derive(Debug, Default, Clone)]
+pub struct TopicTimer {
+    pub status_type: String,
+    pub time: Option<String>,
+    pub based_on_last_post: bool,
+    pub category_id: Option<u64>,
+}
+
+/// An operation applied to many topics at once through `/topics/bulk`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOperation {
+    /// Add tags, keeping the ones already there.
+    AppendTags(Vec<String>),
+    /// Remove these tags, keeping the others. Older Discourse versions
+    /// don't have this operation; see `bulk_remove_topic_tags`.
+    RemoveTags(Vec<String>),
+    ChangeCategory(u64),
+    Close,
+    Archive,
+    Unlist,
+    Delete,
+}
+
+impl BulkOperation {
+    fn payload(&self) -> Vec<(&'static str, String)> {
+        let kind = match self {
+            Self::AppendTags(_) => "append_tags",
+            Self::RemoveTags(_) => "remove_tags",
+            Self::ChangeCategory(_) => "change_category",
+            Self::Close => "close",
+            Self::Archive => "archive",
+            Self::Unlist => "unlist",
+            Self::Delete => "delete",
+        };
+        let mut payload = vec![("operation[type]", kind.to_string())];
+        match self {
+            Self::AppendTags(tags) | Self::RemoveTags(tags) => {
+                for tag in tags {
+                    payload.push(("operation[tags][]", tag.clone()));
+                }
+            }
+            Self::ChangeCategory(category_id) => {
+                payload.push(("operation[category_id]", category_id.to_string()));
+            }
+            _ => {}
+        }
+        payload
+    }
+}
+
 impl DiscourseClient {
     /// Fetch a topic by ID.
     pub fn fetch_topic(&self, topic_id: u64, include_raw: bool) -> Result<TopicResponse> {
@@ -51,6 +174,91 @@ impl DiscourseClient {
         Ok(body)
     }
 
+    /// Fetch every post of a topic, in stream order. `topic` supplies the
+    /// first chunk of posts and the full ID stream; the rest is paged in
+    /// through /t/{id}/posts.json.
+    pub fn fetch_topic_posts(&self, topic: &TopicResponse) -> Result<Vec<Post>> {
+        const CHUNK: usize = 20;
+        let mut by_id: HashMap<u64, Post> = HashMap::new();
+        for post in topic.post_stream.posts.iter() {
+            by_id.insert(post.id, post.clone());
+        }
+        let missing: Vec<u64> = topic
+            .post_stream
+            .stream
+            .iter()
+            .copied()
+            .filter(|id| !by_id.contains_key(id))
+            .collect();
+        for chunk in missing.chunks(CHUNK) {
+            let ids: Vec<String> = chunk
+                .iter()
+                .map(|id| format!("post_ids[]={}", id))
+                .collect();
+            let path = format!("/t/{}/posts.json?include_raw=1&{}", topic.id, ids.join("&"));
+            let response = self.get(&path)?;
+            let status = response.status();
+            let text = response.text().context("reading topic posts response")?;
+            if !status.is_success() {
+                return Err(http_error("topic posts request", status, &text));
+            }
+            let body: TopicPostsResponse =
+                serde_json::from_str(&text).context("parsing topic posts response")?;
+            for post in body.post_stream.posts {
+                by_id.insert(post.id, post);
+            }
+        }
+        if topic.post_stream.stream.is_empty() {
+            return Ok(topic.post_stream.posts.clone());
+        }
+        Ok(topic
+            .post_stream
+            .stream
+            .iter()
+            .filter_map(|id| by_id.remove(id))
+            .collect())
+    }
+
+    /// Fetch every topic listed in /latest.json, across all pages.
+    pub fn fetch_latest_topics(&self) -> Result<Vec<TopicSummary>> {
+        let mut topics = Vec::new();
+        let mut seen = HashSet::new();
+        let mut page = 0;
+        loop {
+            let path = format!("/latest.json?page={}", page);
+            let response = self.get(&path)?;
+            let status = response.status();
+            let text = response.text().context("reading latest topics response")?;
+            if !status.is_success() {
+                return Err(http_error("latest topics request", status, &text));
+            }
+            let body: LatestResponse =
+                serde_json::from_str(&text).context("parsing latest topics json")?;
+            let more = body.topic_list.more_topics_url.is_some();
+            let before = topics.len();
+            topics.extend(
+                body.topic_list
+                    .topics
+                    .into_iter()
+                    .filter(|topic| seen.insert(topic.id)),
+            );
+            if !more || topics.len() == before {
+                return Ok(topics);
+            }
+            page += 1;
+        }
+    }
+
+    /// Whether a topic exists and is visible to the API user.
+    pub fn topic_access(&self, topic_id: u64) -> Result<ResourceAccess> {
+        self.resource_access(&format!("/t/{}.json", topic_id), "topic request")
+    }
+
+    /// Whether a post exists and is visible to the API user.
+    pub fn post_access(&self, post_id: u64) -> Result<ResourceAccess> {
+        self.resource_access(&format!("/posts/{}.json", post_id), "post request")
+    }
+
     /// Fetch a post by ID and return its raw content.
     pub fn fetch_post_raw(&self, post_id: u64) -> Result<Option<String>> {
         Ok(self.fetch_post(post_id)?.raw)
@@ -122,8 +330,22 @@ impl DiscourseClient {
 
     /// Update a post by ID.
     pub fn update_post(&self, post_id: u64, raw: &str) -> Result<()> {
+        self.update_post_with_reason(post_id, raw, None)
+    }
+
+    /// Replace a post's body, recording `edit_reason` in its revision
+    /// history.
+    pub fn update_post_with_reason(
+        &self,
+        post_id: u64,
+        raw: &str,
+        edit_reason: Option<&str>,
+    ) -> Result<()> {
         let path = format!("/posts/{}.json", post_id);
-        let payload = [("post[raw]", raw)];
+        let mut payload = vec![("post[raw]", raw)];
+        if let Some(reason) = edit_reason {
+            payload.push(("post[edit_reason]", reason));
+        }
         let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
         let status = response.status();
         if !status.is_success() {
@@ -135,11 +357,196 @@ impl DiscourseClient {
         Ok(())
     }
 
+    /// Update a topic's title, category and/or tags (PUT /t/-/:id.json).
+    pub fn update_topic(&self, topic_id: u64, update: &TopicUpdate) -> Result<()> {
+        if update.is_empty() {
+            return Ok(());
+        }
+        let path = format!("/t/-/{}.json", topic_id);
+        let payload = update.payload();
+        let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("update topic request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Switch a topic status flag on or off. `status` is one of Discourse's
+    /// `closed`, `archived`, `visible`, `pinned` or `pinned_globally`;
+    /// `until` sets when a pin expires.
+    pub fn set_topic_status(
+        &self,
+        topic_id: u64,
+        status: &str,
+        enabled: bool,
+        until: Option<&str>,
+    ) -> Result<()> {
+        let path = format!("/t/{}/status.json", topic_id);
+        let enabled = enabled.to_string();
+        let mut payload = vec![("status", status), ("enabled", enabled.as_str())];
+        if let Some(until) = until {
+            payload.push(("until", until));
+        }
+        let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("topic status request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Set or, with no `time`, clear a topic timer.
+    pub fn set_topic_timer(&self, topic_id: u64, timer: &TopicTimer) -> Result<()> {
+        let path = format!("/t/{}/timer.json", topic_id);
+        let mut payload = vec![
+            ("status_type", timer.status_type.clone()),
+            ("time", timer.time.clone().unwrap_or_default()),
+            ("based_on_last_post", timer.based_on_last_post.to_string()),
+        ];
+        if let Some(category_id) = timer.category_id {
+            payload.push(("category_id", category_id.to_string()));
+        }
+        let response = self.send_retrying(|| Ok(self.post(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("topic timer request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Make `username` the author of `post_ids` in a topic.
+    pub fn change_post_owner(&self, topic_id: u64, username: &str, post_ids: &[u64]) -> Result<()> {
+        if post_ids.is_empty() {
+            return Err(anyhow!("no post IDs supplied to change owner"));
+        }
+        let path = format!("/t/{}/change-owner.json", topic_id);
+        let payload = change_owner_payload(username, post_ids);
+        let response = self.send_retrying(|| Ok(self.post(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("change owner request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Turn a post's wiki mode on or off.
+    pub fn set_post_wiki(&self, post_id: u64, wiki: bool) -> Result<()> {
+        let path = format!("/posts/{}/wiki.json", post_id);
+        let payload = [("wiki", wiki.to_string())];
+        let response = self.send_retrying(|| Ok(self.put(&path)?.form(&payload)))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("post wiki request", status, &text));
+        }
+        Ok(())
+    }
+
+    /// Apply `operation` to every topic in `topic_ids` with one request.
+    /// Returns the IDs Discourse reports as changed.
+    pub fn bulk_update_topics(
+        &self,
+        topic_ids: &[u64],
+        operation: &BulkOperation,
+    ) -> Result<Vec<u64>> {
+        let (status, text) = self.send_bulk(topic_ids, operation)?;
+        if !status.is_success() {
+            return Err(http_error("bulk topics request", status, &text));
+        }
+        let body: BulkTopicsResponse =
+            serde_json::from_str(&text).context("parsing bulk topics response")?;
+        Ok(body.topic_ids)
+    }
+
+    /// Remove `tags` from every topic in `topic_ids` with one request.
+    /// Returns `None` when the server rejects `remove_tags` as an unknown
+    /// operation (HTTP 400), as older Discourse versions do.
+    pub fn bulk_remove_topic_tags(
+        &self,
+        topic_ids: &[u64],
+        tags: &[String],
+    ) -> Result<Option<Vec<u64>>> {
+        let operation = BulkOperation::RemoveTags(tags.to_vec());
+        let (status, text) = self.send_bulk(topic_ids, &operation)?;
+        if status == StatusCode::BAD_REQUEST {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(http_error("bulk topics request", status, &text));
+        }
+        let body: BulkTopicsResponse =
+            serde_json::from_str(&text).context("parsing bulk topics response")?;
+        Ok(Some(body.topic_ids))
+    }
+
+    fn send_bulk(
+        &self,
+        topic_ids: &[u64],
+        operation: &BulkOperation,
+    ) -> Result<(StatusCode, String)> {
+        let mut payload = operation.payload();
+        for topic_id in topic_ids {
+            payload.push(("topic_ids[]", topic_id.to_string()));
+        }
+        let response = self.send_retrying(|| Ok(self.put("/topics/bulk.json")?.form(&payload)))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .context("reading bulk topics response body")?;
+        Ok((status, text))
+    }
+
+    /// Delete a topic.
+    pub fn delete_topic(&self, topic_id: u64) -> Result<()> {
+        let path = format!("/t/{}.json", topic_id);
+        let response = self.send_retrying(|| self.delete_builder(&path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("delete topic request", status, &text));
+        }
+        Ok(())
+    }
+
     /// Create a new topic in a category.
     pub fn create_topic(&self, category_id: u64, title: &str, raw: &str) -> Result<u64> {
+        self.create_topic_as(category_id, title, raw, None, None)
+    }
+
+    /// Create a topic on behalf of `username` (needs an admin key usable for
+    /// all users), backdated to `created_at` when given.
+    pub fn create_topic_as(
+        &self,
+        category_id: u64,
+        title: &str,
+        raw: &str,
+        username: Option<&str>,
+        created_at: Option<&str>,
+    ) -> Result<u64> {
         let category = category_id.to_string();
-        let payload = [("title", title), ("raw", raw), ("category", &category)];
-        let response = self.send_retrying(|| Ok(self.post("/posts.json")?.form(&payload)))?;
+        let mut payload = vec![("title", title), ("raw", raw), ("category", &category)];
+        if let Some(created_at) = created_at {
+            payload.push(("created_at", created_at));
+        }
+        let response =
+            self.send_retrying(|| Ok(as_user(self.post("/posts.json")?, username).form(&payload)))?;
         let status = response.status();
         let text = response.text().context("reading create response body")?;
         if !status.is_success() {
@@ -215,9 +622,25 @@ impl DiscourseClient {
 
     /// Create a reply post in a topic.
     pub fn create_post(&self, topic_id: u64, raw: &str) -> Result<u64> {
+        self.create_post_as(topic_id, raw, None, None)
+    }
+
+    /// Reply to a topic on behalf of `username`, backdated to `created_at`
+    /// when given. See `create_topic_as`.
+    pub fn create_post_as(
+        &self,
+        topic_id: u64,
+        raw: &str,
+        username: Option<&str>,
+        created_at: Option<&str>,
+    ) -> Result<u64> {
         let topic = topic_id.to_string();
-        let payload = [("topic_id", topic.as_str()), ("raw", raw)];
-        let response = self.send_retrying(|| Ok(self.post("/posts.json")?.form(&payload)))?;
+        let mut payload = vec![("topic_id", topic.as_str()), ("raw", raw)];
+        if let Some(created_at) = created_at {
+            payload.push(("created_at", created_at));
+        }
+        let response =
+            self.send_retrying(|| Ok(as_user(self.post("/posts.json")?, username).form(&payload)))?;
         let status = response.status();
         let text = response.text().context("reading create response body")?;
         if !status.is_success() {
@@ -228,3 +651,80 @@ impl DiscourseClient {
         Ok(body.id)
     }
 }
+
+/// Send `request` as `username` rather than the configured API user. The
+/// request header wins over the client's default one.
+fn as_user(request: RequestBuilder, username: Option<&str>) -> RequestBuilder {
+    match username {
+        Some(username) => request.header("Api-Username", username),
+        None => request,
+    }
+}
+
+fn change_owner_payload(username: &str, post_ids: &[u64]) -> Vec<(&'static str, String)> {
+    let mut payload = vec![("username", username.to_string())];
+    for post_id in post_ids {
+        payload.push(("post_ids[]", post_id.to_string()));
+    }
+    payload
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BulkOperation, TopicUpdate, change_owner_payload};
+
+    fn pairs(payload: &[(&str, String)]) -> Vec<String> {
+        payload
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect()
+    }
+
+    #[test]
+    fn topic_update_sends_only_the_set_fields() {
+        let update = TopicUpdate {
+            title: Some("New title".to_string()),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+            ..TopicUpdate::default()
+        };
+        assert_eq!(
+            pairs(&update.payload()),
+            vec!["title=New title", "tags[]=a", "tags[]=b"]
+        );
+        let update = TopicUpdate {
+            category_id: Some(7),
+            featured_link: Some(String::new()),
+            ..TopicUpdate::default()
+        };
+        assert_eq!(
+            pairs(&update.payload()),
+            vec!["category_id=7", "featured_link="]
+        );
+    }
+
+    #[test]
+    fn empty_tag_list_clears_tags() {
+        let update = TopicUpdate {
+            tags: Some(Vec::new()),
+            ..TopicUpdate::default()
+        };
+        assert_eq!(pairs(&update.payload()), vec!["tags[]="]);
+    }
+
+    #[test]
+    fn change_owner_lists_every_post() {
+        assert_eq!(
+            pairs(&change_owner_payload("alice", &[10, 11])),
+            vec!["username=alice", "post_ids[]=10", "post_ids[]=11"]
+        );
+    }
+
+    #[test]
+    fn bulk_tag_operations_send_their_tags() {
+        let operation = BulkOperation::RemoveTags(vec!["old".to_string()]);
+        assert_eq!(
+            pairs(&operation.payload()),
+            vec!["operation[type]=remove_tags", "operation[tags][]=old"]
+        );
+    }
+}
@@ -23,20 +23,39 @@ pub struct UploadInfo {
     pub height: Option<u64>,
 }
 
+/// One resolved short URL from `/uploads/lookup-urls.json`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UploadLookup {
+    pub short_url: String,
+    pub url: String,
+    #[serde(default)]
+    pub short_path: Option<String>,
+}
+
 impl DiscourseClient {
     /// Upload a file. `upload_type` is Discourse's `type` field — typical
     /// values: `composer` (default; for embedding in posts), `avatar`,
     /// `profile_background`, `card_background`, `custom_emoji`.
     pub fn upload_file(&self, file_path: &Path, upload_type: &str) -> Result<UploadInfo> {
+        let bytes = std::fs::read(file_path)
+            .with_context(|| format!("reading {}", file_path.display()))?;
+        let filename = file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| anyhow!("upload path missing filename: {}", file_path.display()))?;
+        self.upload_bytes(filename, bytes, upload_type)
+    }
+
+    /// Upload in-memory file contents under `filename`. See `upload_file`.
+    pub fn upload_bytes(
+        &self,
+        filename: &str,
+        bytes: Vec<u8>,
+        upload_type: &str,
+    ) -> Result<UploadInfo> {
         let make_form = || -> Result<reqwest::blocking::multipart::Form> {
-            let bytes = std::fs::read(file_path)
-                .with_context(|| format!("reading {}", file_path.display()))?;
-            let filename = file_path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .ok_or_else(|| anyhow!("upload path missing filename: {}", file_path.display()))?
-                .to_string();
-            let part = reqwest::blocking::multipart::Part::bytes(bytes).file_name(filename);
+            let part = reqwest::blocking::multipart::Part::bytes(bytes.clone())
+                .file_name(filename.to_string());
             Ok(reqwest::blocking::multipart::Form::new()
                 .part("file", part)
                 .text("type", upload_type.to_string())
@@ -53,4 +72,38 @@ impl DiscourseClient {
             serde_json::from_str(&text).context("parsing upload response")?;
         Ok(info)
     }
+
+    /// Resolve `upload://` short URLs to their real URLs. Unknown short URLs
+    /// are left out of the result.
+    pub fn lookup_upload_urls(&self, short_urls: &[String]) -> Result<Vec<UploadLookup>> {
+        let payload: Vec<(&str, &str)> = short_urls
+            .iter()
+            .map(|url| ("short_urls[]", url.as_str()))
+            .collect();
+        let response =
+            self.send_retrying(|| Ok(self.post("/uploads/lookup-urls.json")?.form(&payload)))?;
+        let status = response.status();
+        let text = response.text().context("reading upload lookup response")?;
+        if !status.is_success() {
+            return Err(http_error("upload lookup request", status, &text));
+        }
+        let lookups: Vec<UploadLookup> =
+            serde_json::from_str(&text).context("parsing upload lookup response")?;
+        Ok(lookups)
+    }
+
+    /// Download an upload's bytes from the URL returned by
+    /// `lookup_upload_urls`.
+    pub fn download_upload(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self.get_url(url)?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .unwrap_or_else(|_| "<failed to read response body>".to_string());
+            return Err(http_error("upload download request", status, &text));
+        }
+        let bytes = response.bytes().context("reading upload download")?;
+        Ok(bytes.to_vec())
+    }
 }
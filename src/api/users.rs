@@ -1,8 +1,14 @@
 use super::client::DiscourseClient;
 use super::error::http_error;
+use super::search::urlencode_form;
 use anyhow::{Context, Result, anyhow};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashSet;
+
+/// Users per page of /admin/users/list.
+const ADMIN_USERS_PAGE_SIZE: usize = 100;
 
 /// One row from /admin/users/list/<type>.json.
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -27,6 +33,21 @@ pub struct UserSummary {
     pub last_seen_at: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
+    #[serde(default)]
+    pub post_count: Option<u64>,
+}
+
+/// What else `delete_user` does.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DeleteUserOptions {
+    /// Delete the user's posts too.
+    pub delete_posts: bool,
+    /// Stop the email address registering again.
+    pub block_email: bool,
+    /// Stop the user's IP address registering again.
+    pub block_ip: bool,
+    /// Block the URLs in the user's posts.
+    pub block_urls: bool,
 }
 
 /// Distilled /users/<username>.json payload.
@@ -79,6 +100,49 @@ impl DiscourseClient {
         Ok(users)
     }
 
+    /// Fetch every page of an admin user listing.
+    pub fn admin_list_all_users(&self, listing: &str) -> Result<Vec<UserSummary>> {
+        let mut users: Vec<UserSummary> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut page = 1;
+        loop {
+            let batch = self.admin_list_users(listing, page)?;
+            let fresh: Vec<UserSummary> = batch
+                .into_iter()
+                .filter(|user| seen.insert(user.id))
+                .collect();
+            // A short page is the last; an already-seen one means the
+            // server ignored `page`.
+            let done = fresh.len() < ADMIN_USERS_PAGE_SIZE;
+            users.extend(fresh);
+            if done {
+                return Ok(users);
+            }
+            page += 1;
+        }
+    }
+
+    /// Find the user with this exact email address, if there is one.
+    pub fn find_user_by_email(&self, email: &str) -> Result<Option<UserSummary>> {
+        let path = format!(
+            "/admin/users/list/all.json?show_emails=true&email={}",
+            urlencode_form(email)
+        );
+        let response = self.get(&path)?;
+        let status = response.status();
+        let text = response.text().context("reading user list response")?;
+        if !status.is_success() {
+            return Err(http_error("admin user list request", status, &text));
+        }
+        let users: Vec<UserSummary> =
+            serde_json::from_str(&text).context("parsing user list response")?;
+        Ok(users.into_iter().find(|user| {
+            user.email
+                .as_deref()
+                .is_some_and(|found| found.eq_ignore_ascii_case(email))
+        }))
+    }
+
     /// Look up a user by username (public endpoint).
     pub fn fetch_user_detail(&self, username: &str) -> Result<UserDetail> {
         let path = format!("/u/{}.json", username);
@@ -98,6 +162,11 @@ impl DiscourseClient {
         Ok(detail)
     }
 
+    /// Whether a user with this username exists.
+    pub fn user_exists(&self, username: &str) -> Result<bool> {
+        self.resource_exists(&format!("/u/{}.json", username), "user detail request")
+    }
+
     /// Suspend a user by ID. `until` is an ISO-8601 timestamp (or any string
     /// Discourse accepts, like "forever"); `reason` is mandatory from the UI
     /// but Discourse accepts empty via the API.
@@ -129,6 +198,62 @@ impl DiscourseClient {
         self.put_admin_user_action(user_id, "unsilence", &[], "unsilence user request")
     }
 
+    /// Deactivate a user by ID; they must confirm their email again before
+    /// logging in.
+    pub fn deactivate_user(&self, user_id: u64) -> Result<()> {
+        self.put_admin_user_action(user_id, "deactivate", &[], "deactivate user request")
+    }
+
+    /// Delete a user by ID. Discourse refuses users who have posts unless
+    /// `delete_posts` is set.
+    pub fn delete_user(&self, user_id: u64, options: &DeleteUserOptions) -> Result<()> {
+        let path = delete_user_path(user_id, options);
+        let mut attempts = 0u32;
+        let response = self.send_retrying(|| {
+            attempts += 1;
+            self.delete_builder(&path)
+        })?;
+        let status = response.status();
+        // A retried DELETE that finds the user gone means an earlier attempt
+        // went through after all.
+        if status == StatusCode::NOT_FOUND && attempts > 1 {
+            return Ok(());
+        }
+        let text = response
+            .text()
+            .unwrap_or_else(|_| "<failed to read response body>".to_string());
+        if !status.is_success() {
+            return Err(http_error("delete user request", status, &text));
+        }
+        let value: Value = serde_json::from_str(&text).unwrap_or(Value::Null);
+        if value.get("deleted").and_then(Value::as_bool) == Some(false) {
+            return Err(anyhow!(
+                "Discourse did not delete user {}: {}",
+                user_id,
+                text
+            ));
+        }
+        Ok(())
+    }
+
+    /// Anonymize a user by ID, replacing their username, name, email and
+    /// profile. Returns the new username.
+    pub fn anonymize_user(&self, user_id: u64) -> Result<String> {
+        let path = format!("/admin/users/{}/anonymize.json", user_id);
+        let response = self.send_retrying(|| self.put(&path))?;
+        let status = response.status();
+        let text = response.text().context("reading anonymize response")?;
+        if !status.is_success() {
+            return Err(http_error("anonymize user request", status, &text));
+        }
+        let value: Value = serde_json::from_str(&text).context("parsing anonymize response")?;
+        value
+            .get("username")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("anonymize response missing username: {}", text))
+    }
+
     /// Grant admin to a user.
     pub fn grant_admin(&self, user_id: u64) -> Result<()> {
         self.put_admin_user_action(user_id, "grant_admin", &[], "grant admin request")
@@ -268,3 +393,42 @@ impl DiscourseClient {
         Ok(())
     }
 }
+
+/// DELETE path for a user, with the options that are set as query flags.
+fn delete_user_path(user_id: u64, options: &DeleteUserOptions) -> String {
+    let flags: Vec<&str> = [
+        ("delete_posts=true", options.delete_posts),
+        ("block_email=true", options.block_email),
+        ("block_ip=true", options.block_ip),
+        ("block_urls=true", options.block_urls),
+    ]
+    .into_iter()
+    .filter_map(|(flag, set)| set.then_some(flag))
+    .collect();
+    let path = format!("/admin/users/{}.json", user_id);
+    if flags.is_empty() {
+        path
+    } else {
+        format!("{}?{}", path, flags.join("&"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delete_user_path_sets_only_chosen_flags() {
+        let none = DeleteUserOptions::default();
+        assert_eq!(delete_user_path(7, &none), "/admin/users/7.json");
+        let some = DeleteUserOptions {
+            delete_posts: true,
+            block_ip: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            delete_user_path(7, &some),
+            "/admin/users/7.json?delete_posts=true&block_ip=true"
+        );
+    }
+}
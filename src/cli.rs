@@ -1,5 +1,6 @@
-use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -160,6 +161,11 @@ pub enum Commands {
         #[command(subcommand)]
         command: PostCommand,
     },
+    /// Link checks across forum content.
+    Links {
+        #[command(subcommand)]
+        command: LinksCommand,
+    },
     /// Open a Discourse in the default browser.
     #[command(visible_alias = "o")]
     Open {
@@ -347,30 +353,57 @@ pub enum TopicCommand {
         topic_id: u64,
         /// Destination file or directory (auto-derived when omitted).
         local_path: Option<PathBuf>,
+        /// Fetch every post in the thread, not just the opening post.
+        #[arg(long, short = 'a')]
+        all_posts: bool,
+        /// With --all-posts, write one file per post into a directory.
+        #[arg(long, requires = "all_posts")]
+        split: bool,
+        /// Download `upload://` files into `assets/` and link to the local copies.
+        #[arg(long)]
+        download_uploads: bool,
     },
     /// Push a local Markdown file to a topic.
     #[command(visible_alias = "ps")]
     Push {
         /// Discourse name.
         discourse: String,
-        /// Topic ID.
-        topic_id: u64,
+        /// Topic ID, or the local path when the ID comes from the file's
+        /// front matter.
+        #[arg(value_name = "TOPIC_ID")]
+        topic_or_path: String,
         /// Local Markdown file path.
-        local_path: PathBuf,
+        local_path: Option<PathBuf>,
     },
-    /// Sync a topic and local Markdown file using newest timestamp.
+    /// Sync a topic and local Markdown file, three-way merging changes from both sides.
     #[command(visible_alias = "sy")]
     Sync {
         /// Discourse name.
         discourse: String,
-        /// Topic ID.
-        topic_id: u64,
+        /// Topic ID, or the local path when the ID comes from the file's
+        /// front matter.
+        #[arg(value_name = "TOPIC_ID")]
+        topic_or_path: String,
         /// Local Markdown file path.
-        local_path: PathBuf,
+        local_path: Option<PathBuf>,
         /// Skip sync confirmation prompt.
         #[arg(long, short = 'y')]
         yes: bool,
     },
+    /// Show how a local Markdown file or directory differs from the forum.
+    #[command(visible_alias = "d")]
+    Diff {
+        /// Discourse name.
+        discourse: String,
+        /// Local Markdown file, or a directory pulled by `category pull`.
+        local_path: PathBuf,
+        /// Post ID to compare with (defaults to the file's post, or its topic's first post).
+        #[arg(long)]
+        post: Option<u64>,
+        /// Print a per-file summary of changed lines instead of the diff.
+        #[arg(long)]
+        stat: bool,
+    },
     /// Reply to a topic with content from a file or stdin.
     #[command(visible_alias = "r")]
     Reply {
@@ -394,6 +427,180 @@ pub enum TopicCommand {
         /// Input file path. Reads stdin when omitted or `-`.
         local_path: Option<PathBuf>,
     },
+    /// Change a topic's title, category, tags or featured link.
+    #[command(visible_alias = "e")]
+    Edit {
+        /// Discourse name.
+        discourse: String,
+        /// Topic ID.
+        topic_id: u64,
+        /// New title.
+        #[arg(long, short = 't')]
+        title: Option<String>,
+        /// New category ID or slug.
+        #[arg(long, short = 'c')]
+        category: Option<String>,
+        /// Replace the tags with this comma-separated list (empty clears them).
+        #[arg(long, value_delimiter = ',', num_args = 0..=1)]
+        tags: Option<Vec<String>>,
+        /// New featured link URL (empty removes it).
+        #[arg(long)]
+        featured_link: Option<String>,
+    },
+    /// Reassign posts in a topic to another user.
+    #[command(visible_alias = "co")]
+    ChangeOwner {
+        /// Discourse name.
+        discourse: String,
+        /// Topic ID.
+        topic_id: u64,
+        /// Username of the new owner.
+        username: String,
+        /// Post ID to reassign (repeatable; defaults to the opening post).
+        #[arg(long = "post", short = 'p')]
+        post_ids: Vec<u64>,
+    },
+    /// Copy a topic with all its posts to a category on another Discourse.
+    #[command(visible_alias = "cp")]
+    Copy {
+        /// Source discourse name.
+        discourse: String,
+        /// Topic ID on the source.
+        topic_id: u64,
+        /// Target discourse name (defaults to source when omitted).
+        #[arg(long, short = 't')]
+        target: Option<String>,
+        /// Category ID or slug on the target.
+        #[arg(long, short = 'c')]
+        category: String,
+    },
+    /// Apply one action to every topic matching a search query.
+    #[command(visible_alias = "b")]
+    Bulk {
+        /// Discourse name.
+        discourse: String,
+        /// Search query selecting the topics, in Discourse search syntax.
+        #[arg(long, short = 'q')]
+        query: String,
+        /// What to do with the matching topics.
+        #[arg(long, short = 'a', value_enum)]
+        action: BulkAction,
+        /// Comma-separated tags, for add-tags and remove-tags.
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required_if_eq_any([("action", "add-tags"), ("action", "remove-tags")])
+        )]
+        tags: Vec<String>,
+        /// Destination category ID or slug, for move.
+        #[arg(long, short = 'c', required_if_eq("action", "move"))]
+        category: Option<String>,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Close topics.
+    #[command(visible_alias = "cl")]
+    Close {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Reopen closed topics.
+    #[command(visible_alias = "op")]
+    Open {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Pin topics in their category, or on every topic list with --global.
+    #[command(visible_alias = "pn")]
+    Pin {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+        /// Pin on every topic list, not just the topic's category.
+        #[arg(long, short = 'g')]
+        global: bool,
+        /// When the pin expires (a date or timestamp, e.g. 2026-12-31).
+        #[arg(long, short = 'u')]
+        until: Option<String>,
+    },
+    /// Unpin topics.
+    #[command(visible_alias = "up")]
+    Unpin {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Archive topics, freezing them read-only.
+    #[command(visible_alias = "ar")]
+    Archive {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Unarchive topics.
+    #[command(visible_alias = "ua")]
+    Unarchive {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Unlist topics, hiding them from topic lists.
+    #[command(visible_alias = "ul")]
+    Unlist {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// List unlisted topics again.
+    #[command(visible_alias = "rl")]
+    Relist {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+    },
+    /// Set or clear a timer that closes, opens, deletes or publishes topics.
+    #[command(visible_alias = "tm")]
+    Timer {
+        /// Discourse name.
+        discourse: String,
+        /// Topic IDs.
+        #[arg(required = true)]
+        topic_ids: Vec<u64>,
+        /// What the timer does.
+        #[arg(long, short = 'a', value_enum)]
+        action: TimerAction,
+        /// Hours from now, or a date or timestamp (e.g. 48, 2026-12-31T09:00:00Z).
+        #[arg(long, short = 't', required_unless_present = "clear")]
+        time: Option<String>,
+        /// Remove the topic's timer of this kind instead of setting one.
+        #[arg(long, conflicts_with = "time")]
+        clear: bool,
+        /// Count the time from the last post rather than from now (auto-close only).
+        #[arg(long)]
+        based_on_last_post: bool,
+        /// Destination category ID or slug, for publish-to-category.
+        #[arg(long, short = 'c', required_if_eq("action", "publish-to-category"))]
+        category: Option<String>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -423,6 +630,13 @@ pub enum CategoryCommand {
         target: Option<String>,
         /// Category ID or slug.
         category: String,
+        /// Also copy group permissions, settings, the description and every
+        /// subcategory.
+        #[arg(long, short = 'd')]
+        deep: bool,
+        /// Keep the original name and slug (only with a different --target).
+        #[arg(long, short = 'k')]
+        keep_name: bool,
     },
     /// Pull all topics from a category into local Markdown files.
     #[command(visible_alias = "pl")]
@@ -433,6 +647,21 @@ pub enum CategoryCommand {
         category: String,
         /// Destination directory (auto-derived when omitted).
         local_path: Option<PathBuf>,
+        /// Also pull topics from subcategories.
+        #[arg(long)]
+        include_subcategories: bool,
+        /// Pull each subcategory into its own nested directory.
+        #[arg(long, short = 'r', conflicts_with = "include_subcategories")]
+        recursive: bool,
+        /// Fetch every post in each thread, not just the opening post.
+        #[arg(long, short = 'a')]
+        all_posts: bool,
+        /// With --all-posts, write one directory per topic and one file per post.
+        #[arg(long, requires = "all_posts")]
+        split: bool,
+        /// Download `upload://` files into `assets/` and link to the local copies.
+        #[arg(long)]
+        download_uploads: bool,
     },
     /// Push local Markdown files into a category.
     #[command(visible_alias = "ps")]
@@ -443,9 +672,126 @@ pub enum CategoryCommand {
         category: String,
         /// Local directory containing Markdown files.
         local_path: PathBuf,
+        /// Also match files against topics in subcategories.
+        #[arg(long)]
+        include_subcategories: bool,
+        /// Push subdirectories into matching subcategories, creating any that are missing.
+        #[arg(long, short = 'r', conflicts_with = "include_subcategories")]
+        recursive: bool,
+        /// What to do with topics whose files were removed (offers to close them when omitted).
+        #[arg(long, value_enum)]
+        removed: Option<RemovedTopicAction>,
+    },
+    /// Change a category's settings.
+    #[command(visible_alias = "e")]
+    Edit {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        #[command(flatten)]
+        fields: Box<CategoryFields>,
+    },
+    /// Move a category under another parent or to another position.
+    #[command(visible_alias = "mv")]
+    Move {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// New parent category ID or slug.
+        #[arg(long, short = 'p', conflicts_with = "top_level")]
+        parent: Option<String>,
+        /// Make it a top-level category.
+        #[arg(long)]
+        top_level: bool,
+        /// New sort position.
+        #[arg(long)]
+        position: Option<i64>,
+    },
+    /// Set category positions from an ordered list.
+    #[command(visible_alias = "ro")]
+    Reorder {
+        /// Discourse name.
+        discourse: String,
+        /// Category IDs or slugs, first to last. Unlisted categories keep
+        /// their order after these.
+        #[arg(required = true)]
+        categories: Vec<String>,
+    },
+    /// Delete a category.
+    #[command(visible_alias = "rm")]
+    Delete {
+        /// Discourse name.
+        discourse: String,
+        /// Category ID or slug.
+        category: String,
+        /// Delete the category's topics too instead of refusing.
+        #[arg(long)]
+        force: bool,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Write every category's editable settings to a YAML file.
+    #[command(visible_alias = "ex")]
+    Export {
+        /// Discourse name.
+        discourse: String,
+        /// Output file (prints to stdout when omitted).
+        file: Option<PathBuf>,
+    },
+    /// Create and update categories to match a YAML file from `category export`.
+    #[command(visible_alias = "ap")]
+    Apply {
+        /// Discourse name.
+        discourse: String,
+        /// YAML file of category settings.
+        file: PathBuf,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
     },
 }
 
+/// Settings `category edit` can change; each one left out stays as it is.
+#[derive(Args)]
+pub struct CategoryFields {
+    /// New name.
+    #[arg(long)]
+    pub name: Option<String>,
+    /// New slug.
+    #[arg(long)]
+    pub slug: Option<String>,
+    /// Background colour as hex, e.g. `0088CC`.
+    #[arg(long)]
+    pub color: Option<String>,
+    /// Text colour as hex.
+    #[arg(long)]
+    pub text_color: Option<String>,
+    /// New description (the Markdown of the "About" post).
+    #[arg(long)]
+    pub description: Option<String>,
+    /// Template for new topics (empty removes it).
+    #[arg(long)]
+    pub topic_template: Option<String>,
+    /// Default topic list, e.g. `latest` or `top`.
+    #[arg(long)]
+    pub default_view: Option<String>,
+    /// Only allow these tags (comma-separated; empty allows any).
+    #[arg(long, value_delimiter = ',', num_args = 0..=1)]
+    pub allowed_tags: Option<Vec<String>>,
+    /// Only allow tags from these tag groups (comma-separated; empty allows any).
+    #[arg(long, value_delimiter = ',', num_args = 0..=1)]
+    pub allowed_tag_groups: Option<Vec<String>>,
+    /// Number of tags each topic must have.
+    #[arg(long)]
+    pub minimum_required_tags: Option<u64>,
+    /// Replace the access list with `group=level` (full, reply or see; repeatable).
+    #[arg(long = "permission", short = 'p')]
+    pub permissions: Vec<String>,
+}
+
 #[derive(Subcommand)]
 pub enum GroupCommand {
     /// List groups.
@@ -508,6 +854,119 @@ pub enum GroupCommand {
         #[arg(long)]
         notify: bool,
     },
+    /// Create a group.
+    #[command(visible_alias = "cr")]
+    Create {
+        /// Discourse name.
+        discourse: String,
+        /// Group name (lowercase, no spaces).
+        name: String,
+        #[command(flatten)]
+        fields: Box<GroupFields>,
+    },
+    /// Change a group's settings.
+    #[command(visible_alias = "e")]
+    Edit {
+        /// Discourse name.
+        discourse: String,
+        /// Group ID.
+        group: u64,
+        /// New group name.
+        #[arg(long)]
+        name: Option<String>,
+        #[command(flatten)]
+        fields: Box<GroupFields>,
+    },
+    /// Delete a group.
+    #[command(visible_alias = "rm")]
+    Delete {
+        /// Discourse name.
+        discourse: String,
+        /// Group ID.
+        group: u64,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Create and update groups to match a YAML file.
+    #[command(visible_alias = "ap")]
+    Apply {
+        /// Discourse name.
+        discourse: String,
+        /// YAML file of group settings.
+        file: PathBuf,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Make a group's members match a file of usernames or emails.
+    #[command(visible_alias = "sy")]
+    Sync {
+        /// Discourse name.
+        discourse: String,
+        /// Group ID.
+        group: u64,
+        /// CSV file of `username-or-email[,owner]` lines (`#` comments and
+        /// a header row are ignored).
+        local_path: PathBuf,
+        /// Only add members and owners; never remove or demote anyone.
+        #[arg(long)]
+        no_remove: bool,
+        /// Send Discourse notifications to added users.
+        #[arg(long)]
+        notify: bool,
+        /// Skip emails with no account instead of stopping. A member whose
+        /// email was skipped counts as unlisted and may be removed.
+        #[arg(long)]
+        skip_unknown: bool,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Tabulate which users are in which groups across discourses.
+    #[command(visible_alias = "mx")]
+    Matrix {
+        /// Only include discourses with these tags (comma/semicolon separated, match-any).
+        #[arg(long, value_name = "tag1,tag2")]
+        tags: Option<String>,
+        /// Output format.
+        #[arg(long, short = 'f', value_enum, default_value = "markdown-table")]
+        format: MatrixFormat,
+    },
+}
+
+/// Settings `group create` and `group edit` can set; each one left out
+/// stays as it is.
+#[derive(Args, Default)]
+pub struct GroupFields {
+    /// Display name.
+    #[arg(long)]
+    pub full_name: Option<String>,
+    /// Who can see the group.
+    #[arg(long, value_enum)]
+    pub visibility: Option<GroupVisibility>,
+    /// Who can @mention the group.
+    #[arg(long, value_enum)]
+    pub mentionable: Option<GroupAliasLevel>,
+    /// Who can message the group.
+    #[arg(long, value_enum)]
+    pub messageable: Option<GroupAliasLevel>,
+    /// Add users with these email domains automatically (comma-separated;
+    /// empty clears them).
+    #[arg(long, value_delimiter = ',', num_args = 0..=1)]
+    pub email_domains: Option<Vec<String>>,
+    /// Notification level new members start at.
+    #[arg(long, value_enum)]
+    pub default_notification: Option<GroupNotificationLevel>,
+    /// Flair icon name, e.g. `far-star`.
+    #[arg(long)]
+    pub flair_icon: Option<String>,
+    /// Flair colour as hex.
+    #[arg(long)]
+    pub flair_color: Option<String>,
+    /// Flair background colour as hex.
+    #[arg(long)]
+    pub flair_background_color: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -785,11 +1244,25 @@ pub enum UserCommand {
         #[arg(long, short = 'l', default_value = "active")]
         listing: String,
         /// Page number (Discourse paginates 100 per page).
-        #[arg(long, short = 'p', default_value_t = 1)]
+        #[arg(long, short = 'p', default_value_t = 1, conflicts_with = "all")]
         page: u32,
+        /// Fetch every page instead of one.
+        #[arg(long, short = 'a')]
+        all: bool,
+        #[command(flatten)]
+        filters: Box<UserFilterArgs>,
         /// Output format.
         #[arg(long, short = 'f', value_enum, default_value = "text")]
-        format: ListFormat,
+        format: UserListFormat,
+        /// Columns for csv and markdown-table output (comma-separated).
+        #[arg(
+            long,
+            short = 'c',
+            value_enum,
+            value_delimiter = ',',
+            default_value = "username,email,trust_level,last_seen_at,created_at,post_count"
+        )]
+        columns: Vec<UserColumn>,
     },
     /// Show detailed info for a user.
     #[command(visible_alias = "i")]
@@ -848,6 +1321,62 @@ pub enum UserCommand {
         /// Username.
         username: String,
     },
+    /// Suspend, silence, delete or deactivate many users from a CSV file.
+    ///
+    /// Deletions are confirmed with the same y/N prompt as every other row,
+    /// not by typing each username back as `user delete` asks.
+    #[command(visible_alias = "b")]
+    Bulk {
+        /// Discourse name.
+        discourse: String,
+        /// CSV file of `user,action,until,reason` rows (`#` comments and a
+        /// header row are ignored).
+        local_path: PathBuf,
+        /// Where to record each row's result [default: <file>.report.csv].
+        #[arg(long, short = 'r')]
+        report: Option<PathBuf>,
+        /// Delete the posts of users on `delete` rows too (required if they
+        /// have any).
+        #[arg(long)]
+        delete_posts: bool,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Delete a user, asking for the username to be typed back.
+    #[command(visible_alias = "rm")]
+    Delete {
+        /// Discourse name.
+        discourse: String,
+        /// Username.
+        username: String,
+        /// Delete the user's posts too (required if they have any).
+        #[arg(long)]
+        delete_posts: bool,
+        /// Stop the email address registering again.
+        #[arg(long)]
+        block_email: bool,
+        /// Stop the user's IP address registering again.
+        #[arg(long)]
+        block_ip: bool,
+        /// Block the URLs in the user's posts.
+        #[arg(long)]
+        block_urls: bool,
+        /// Skip the typed confirmation.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+    /// Anonymize a user, asking for the username to be typed back.
+    #[command(visible_alias = "anon")]
+    Anonymize {
+        /// Discourse name.
+        discourse: String,
+        /// Username.
+        username: String,
+        /// Skip the typed confirmation.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
     /// Grant the user the admin or moderator role.
     #[command(visible_alias = "pr")]
     Promote {
@@ -973,6 +1502,61 @@ pub enum AnalyticsFormat {
     Csv,
 }
 
+/// Filters for `user list`; a user must pass every one given.
+#[derive(Args, Default)]
+pub struct UserFilterArgs {
+    /// Only these trust levels (comma-separated, e.g. `0,1`).
+    #[arg(long, value_delimiter = ',')]
+    pub trust_level: Vec<u64>,
+    /// Only users last seen before this: a duration ago (`90d`, `6m`) or an
+    /// ISO-8601 date. Users never seen are included.
+    #[arg(long, value_name = "WHEN")]
+    pub last_seen_before: Option<String>,
+    /// Only users created after this: a duration ago (`30d`) or an ISO-8601
+    /// date.
+    #[arg(long, value_name = "WHEN")]
+    pub created_after: Option<String>,
+    /// Only users with an email address at these domains (comma-separated).
+    #[arg(long, value_delimiter = ',')]
+    pub email_domain: Vec<String>,
+    /// Only members (or owners) of this group, by name or ID.
+    #[arg(long, short = 'g')]
+    pub group: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum UserListFormat {
+    /// Plain text.
+    Text,
+    /// Pretty JSON.
+    Json,
+    /// YAML.
+    #[value(alias = "yml")]
+    Yaml,
+    /// CSV with a header row.
+    Csv,
+    /// Markdown table.
+    #[value(alias = "md")]
+    MarkdownTable,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+#[value(rename_all = "snake_case")]
+pub enum UserColumn {
+    Id,
+    Username,
+    Name,
+    Email,
+    TrustLevel,
+    Admin,
+    Moderator,
+    Suspended,
+    Silenced,
+    LastSeenAt,
+    CreatedAt,
+    PostCount,
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 pub enum ActivityFormatArg {
     Text,
@@ -1059,6 +1643,112 @@ pub enum PostCommand {
         #[arg(long = "to-topic", short = 't')]
         to_topic: u64,
     },
+    /// Turn a post's wiki mode on or off.
+    #[command(visible_alias = "w")]
+    Wiki {
+        /// Discourse name.
+        discourse: String,
+        /// Post ID.
+        post_id: u64,
+        /// Whether the post should be a wiki.
+        #[arg(value_enum)]
+        state: Toggle,
+    },
+    /// List a post's revisions with editor, time and edit reason.
+    #[command(visible_alias = "log")]
+    History {
+        /// Discourse name.
+        discourse: String,
+        /// Post ID.
+        post_id: u64,
+        /// Output format.
+        #[arg(long, short = 'f', value_enum, default_value = "text")]
+        format: ListFormat,
+    },
+    /// Print a post's raw Markdown as it was after a revision.
+    #[command(visible_alias = "sr")]
+    ShowRevision {
+        /// Discourse name.
+        discourse: String,
+        /// Post ID.
+        post_id: u64,
+        /// Revision number (from `post history`).
+        revision: u64,
+    },
+    /// Restore a post's body to how it was after a revision.
+    #[command(visible_alias = "rv")]
+    Revert {
+        /// Discourse name.
+        discourse: String,
+        /// Post ID.
+        post_id: u64,
+        /// Revision number to restore (from `post history`).
+        revision: u64,
+    },
+    /// Regex search-and-replace across posts, with a diff preview and a
+    /// JSON log of every change.
+    #[command(visible_alias = "rp")]
+    Replace {
+        /// Discourse name.
+        discourse: String,
+        /// Regular expression to match in each post's raw Markdown.
+        #[arg(long, short = 'p')]
+        pattern: String,
+        /// Replacement text; `$1` / `${name}` refer to capture groups.
+        #[arg(long, short = 'r')]
+        replacement: String,
+        /// Search terms used to find candidate posts (default: the longest
+        /// literal run in the pattern).
+        #[arg(long, short = 's')]
+        search: Option<String>,
+        /// Only posts in this category (slug or ID).
+        #[arg(long)]
+        category: Option<String>,
+        /// Only posts made since this date or duration (e.g. 2025-01-01, 30d).
+        #[arg(long)]
+        since: Option<String>,
+        /// Only posts by this user.
+        #[arg(long, short = 'u')]
+        user: Option<String>,
+        /// Edit reason recorded on each revision.
+        #[arg(long)]
+        reason: Option<String>,
+        /// Where to write the change log (default: dsc-replace-<discourse>-<time>.json).
+        #[arg(long)]
+        log: Option<PathBuf>,
+        /// Skip the confirmation prompt.
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum LinksCommand {
+    /// Find broken internal and external links in posts.
+    #[command(visible_alias = "c")]
+    Check {
+        /// Discourse name.
+        discourse: String,
+        /// Only topics in this category and its subcategories (ID or slug).
+        /// Default: every topic on the site.
+        #[arg(long, short = 'c')]
+        category: Option<String>,
+        /// Report every link, not just broken ones.
+        #[arg(long, short = 'a')]
+        all: bool,
+        /// External URLs probed at once.
+        #[arg(long, short = 'j', default_value_t = 8)]
+        concurrency: usize,
+        /// Most requests per second sent to any one external host.
+        #[arg(long, default_value_t = 2.0)]
+        per_host_rate: f64,
+        /// Seconds to wait for each external URL.
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+        /// Output format.
+        #[arg(long, short = 'f', value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
 }
 
 #[derive(Subcommand)]
@@ -1174,6 +1864,92 @@ pub enum OutputFormat {
     Urls,
 }
 
+/// What `category push` does with a topic whose file was removed.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RemovedTopicAction {
+    /// Leave the topic as it is.
+    Keep,
+    /// Close the topic.
+    Close,
+    /// Unlist the topic.
+    Unlist,
+    /// Delete the topic.
+    Delete,
+}
+
+/// Who can see a group.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupVisibility {
+    Public,
+    LoggedOnUsers,
+    Members,
+    Staff,
+    Owners,
+}
+
+/// Who can @mention or message a group.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupAliasLevel {
+    Nobody,
+    OnlyAdmins,
+    ModsAndAdmins,
+    MembersModsAndAdmins,
+    OwnersModsAndAdmins,
+    Everyone,
+}
+
+/// The notification level new group members start at.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupNotificationLevel {
+    Muted,
+    Regular,
+    Tracking,
+    Watching,
+    WatchingFirstPost,
+}
+
+/// An on/off switch argument.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+/// What `topic bulk` does to each matching topic.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum BulkAction {
+    /// Add the --tags to each topic.
+    AddTags,
+    /// Remove the --tags from each topic.
+    RemoveTags,
+    /// Move each topic to --category.
+    Move,
+    /// Close each topic.
+    Close,
+    /// Archive each topic.
+    Archive,
+    /// Unlist each topic.
+    Unlist,
+    /// Delete each topic.
+    Delete,
+}
+
+/// What a topic timer does when it fires.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum TimerAction {
+    /// Close the topic.
+    Close,
+    /// Open the topic.
+    Open,
+    /// Delete the topic.
+    Delete,
+    /// Publish the topic to another category.
+    PublishToCategory,
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 pub enum ListFormat {
     /// Plain text.
@@ -1185,6 +1961,30 @@ pub enum ListFormat {
     Yaml,
 }
 
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ReportFormat {
+    /// Plain text.
+    Text,
+    /// Pretty JSON.
+    Json,
+    /// YAML.
+    #[value(alias = "yml")]
+    Yaml,
+    /// CSV with a header row.
+    Csv,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum MatrixFormat {
+    /// CSV with a header row.
+    Csv,
+    /// Pretty JSON.
+    Json,
+    /// Markdown table.
+    #[value(alias = "md")]
+    MarkdownTable,
+}
+
 #[derive(ValueEnum, Clone, Copy)]
 pub enum StructuredFormat {
     /// Pretty JSON.
@@ -0,0 +1,669 @@
+use crate::api::DiscourseClient;
+use crate::utils::ensure_dir;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Per-directory record of files already uploaded, stored beside the
+/// Markdown it serves.
+const UPLOAD_CACHE_FILE: &str = ".dsc-uploads.json";
+
+/// Folder, beside the Markdown, that pulled uploads are saved into.
+pub(crate) const ASSETS_DIR: &str = "assets";
+
+/// A link or image destination found in a Markdown body.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Link {
+    /// Byte range of the destination within the body.
+    pub range: Range<usize>,
+    /// Byte offset of the `]` closing a plain link's text, where Discourse's
+    /// `|attachment` suffix goes. `None` for images.
+    pub label_end: Option<usize>,
+    pub image: bool,
+}
+
+/// One uploaded file, keyed by content hash in the cache.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CachedUpload {
+    pub short_url: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub filename: Option<String>,
+}
+
+/// Uploads known per Discourse: `discourse name -> content hash -> upload`.
+#[derive(Debug, Default)]
+pub(crate) struct UploadCache {
+    path: PathBuf,
+    entries: BTreeMap<String, BTreeMap<String, CachedUpload>>,
+    dirty: bool,
+}
+
+impl UploadCache {
+    /// Load the cache for `dir`, or start an empty one.
+    pub(crate) fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(UPLOAD_CACHE_FILE);
+        let entries = if path.exists() {
+            let raw =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path,
+            entries,
+            dirty: false,
+        })
+    }
+
+    pub(crate) fn get(&self, discourse_name: &str, hash: &str) -> Option<&CachedUpload> {
+        self.entries.get(discourse_name)?.get(hash)
+    }
+
+    pub(crate) fn insert(&mut self, discourse_name: &str, hash: String, upload: CachedUpload) {
+        self.entries
+            .entry(discourse_name.to_string())
+            .or_default()
+            .insert(hash, upload);
+        self.dirty = true;
+    }
+
+    /// Write the cache back if anything was added.
+    pub(crate) fn save(&self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let raw =
+            serde_json::to_string_pretty(&self.entries).context("serialising upload cache")?;
+        fs::write(&self.path, raw).with_context(|| format!("writing {}", self.path.display()))
+    }
+}
+
+/// Hex SHA-256 of a file's contents.
+pub(crate) fn content_hash(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Upload every local file the body links to and point the links at the
+/// returned `upload://` short URLs. Relative paths resolve against
+/// `base_dir`; files already in `base_dir`'s upload cache aren't sent again.
+/// Under `dry_run` nothing is uploaded and the body is returned unchanged.
+pub(crate) fn upload_local_assets(
+    client: &DiscourseClient,
+    discourse_name: &str,
+    base_dir: &Path,
+    body: &str,
+    dry_run: bool,
+) -> Result<String> {
+    let mut cache = UploadCache::load(base_dir)?;
+    let mut replacements: Vec<(Link, String)> = Vec::new();
+    for link in find_links(body) {
+        let target = &body[link.range.clone()];
+        let Some(path) = local_file(base_dir, target, link.image) else {
+            continue;
+        };
+        let hash = content_hash(&path)?;
+        let short_url = match cache.get(discourse_name, &hash) {
+            Some(cached) => cached.short_url.clone(),
+            None if dry_run => {
+                println!(
+                    "[dry-run] {}: would upload {}",
+                    discourse_name,
+                    path.display()
+                );
+                continue;
+            }
+            None => {
+                let info = client.upload_file(&path, "composer")?;
+                let short_url = info.short_url.clone().unwrap_or_else(|| info.url.clone());
+                println!("Uploaded {} -> {}", path.display(), short_url);
+                cache.insert(
+                    discourse_name,
+                    hash,
+                    CachedUpload {
+                        short_url: short_url.clone(),
+                        url: Some(info.url),
+                        filename: Some(info.original_filename),
+                    },
+                );
+                short_url
+            }
+        };
+        replacements.push((link, short_url));
+    }
+    cache.save()?;
+    Ok(rewrite_links(body, &replacements))
+}
+
+/// Rewrite links to local files that were uploaded before to their
+/// `upload://` URLs, without uploading anything new. Lets read-only
+/// commands compare a local file with the forum in the forum's terms.
+pub(crate) fn map_uploaded_assets(
+    discourse_name: &str,
+    base_dir: &Path,
+    body: &str,
+) -> Result<String> {
+    let cache = UploadCache::load(base_dir)?;
+    let mut replacements: Vec<(Link, String)> = Vec::new();
+    for link in find_links(body) {
+        let target = &body[link.range.clone()];
+        let Some(path) = local_file(base_dir, target, link.image) else {
+            continue;
+        };
+        if let Some(cached) = cache.get(discourse_name, &content_hash(&path)?) {
+            let short_url = cached.short_url.clone();
+            replacements.push((link, short_url));
+        }
+    }
+    Ok(rewrite_links(body, &replacements))
+}
+
+/// Undo `map_uploaded_assets` on a body about to be written over
+/// `local_body`: point each `upload://` link back at the local file
+/// `local_body` used for that upload, so its `assets/` paths survive a pull
+/// or merge.
+pub(crate) fn restore_local_assets(
+    discourse_name: &str,
+    base_dir: &Path,
+    local_body: &str,
+    body: &str,
+) -> Result<String> {
+    let cache = UploadCache::load(base_dir)?;
+    let mut local: HashMap<String, String> = HashMap::new();
+    for link in find_links(local_body) {
+        let target = &local_body[link.range.clone()];
+        let Some(path) = local_file(base_dir, target, link.image) else {
+            continue;
+        };
+        if let Some(cached) = cache.get(discourse_name, &content_hash(&path)?) {
+            local.insert(cached.short_url.clone(), target.to_string());
+        }
+    }
+    let replacements: Vec<(Link, String)> = upload_links(body)
+        .into_iter()
+        .filter_map(|link| {
+            let target = local.get(&body[link.range.clone()])?.clone();
+            Some((link, target))
+        })
+        .collect();
+    Ok(rewrite_links(body, &replacements))
+}
+
+/// How a pull treats `upload://` links: left as they are, or downloaded
+/// into `assets/` with `--download-uploads`.
+pub(crate) struct PullAssets<'a> {
+    client: &'a DiscourseClient,
+    discourse_name: &'a str,
+    download: bool,
+}
+
+impl<'a> PullAssets<'a> {
+    pub(crate) fn new(
+        client: &'a DiscourseClient,
+        discourse_name: &'a str,
+        download: bool,
+    ) -> Self {
+        Self {
+            client,
+            discourse_name,
+            download,
+        }
+    }
+
+    /// Body to write for a Markdown file in `base_dir`.
+    pub(crate) fn localise(&self, base_dir: &Path, body: &str) -> Result<String> {
+        if self.download {
+            download_uploads(self.client, self.discourse_name, base_dir, body)
+        } else {
+            Ok(body.to_string())
+        }
+    }
+}
+
+/// Download every `upload://` file the body links to into `assets/` under
+/// `base_dir` and point the links at the local copies. Each download is
+/// recorded in the upload cache, so a later push maps it back to the same
+/// short URL instead of uploading it again.
+pub(crate) fn download_uploads(
+    client: &DiscourseClient,
+    discourse_name: &str,
+    base_dir: &Path,
+    body: &str,
+) -> Result<String> {
+    let links = upload_links(body);
+    if links.is_empty() {
+        return Ok(body.to_string());
+    }
+    let mut short_urls: Vec<String> = links
+        .iter()
+        .map(|link| body[link.range.clone()].to_string())
+        .collect();
+    short_urls.sort();
+    short_urls.dedup();
+    let resolved: HashMap<String, String> = client
+        .lookup_upload_urls(&short_urls)?
+        .into_iter()
+        .map(|lookup| (lookup.short_url, lookup.url))
+        .collect();
+
+    let mut cache = UploadCache::load(base_dir)?;
+    let assets = base_dir.join(ASSETS_DIR);
+    let mut local: HashMap<String, String> = HashMap::new();
+    for short_url in &short_urls {
+        let filename = short_url.trim_start_matches("upload://");
+        let Some(url) = resolved
+            .get(short_url)
+            .filter(|_| is_plain_filename(filename))
+        else {
+            eprintln!("Upload not found, leaving link as is: {}", short_url);
+            continue;
+        };
+        let path = assets.join(filename);
+        if !path.is_file() {
+            ensure_dir(&assets)?;
+            let bytes = client.download_upload(url)?;
+            fs::write(&path, bytes).with_context(|| format!("writing {}", path.display()))?;
+        }
+        cache.insert(
+            discourse_name,
+            content_hash(&path)?,
+            CachedUpload {
+                short_url: short_url.clone(),
+                url: Some(url.clone()),
+                filename: Some(filename.to_string()),
+            },
+        );
+        local.insert(short_url.clone(), format!("{}/{}", ASSETS_DIR, filename));
+    }
+    cache.save()?;
+
+    // Attachments already carry `|attachment`, so only destinations change.
+    let replacements: Vec<(Link, String)> = links
+        .into_iter()
+        .filter_map(|link| {
+            let destination = local.get(&body[link.range.clone()])?.clone();
+            Some((
+                Link {
+                    label_end: None,
+                    ..link
+                },
+                destination,
+            ))
+        })
+        .collect();
+    Ok(rewrite_links(body, &replacements))
+}
+
+/// Re-upload every `upload://` file the body links to from `source` to
+/// `target` and point the links at the new short URLs. `copied` maps source
+/// short URLs to target ones across calls, so a file several posts share is
+/// sent once.
+pub(crate) fn copy_uploads(
+    source: &DiscourseClient,
+    target: &DiscourseClient,
+    body: &str,
+    copied: &mut HashMap<String, String>,
+) -> Result<String> {
+    let links = upload_links(body);
+    let mut pending: Vec<String> = links
+        .iter()
+        .map(|link| body[link.range.clone()].to_string())
+        .filter(|short_url| !copied.contains_key(short_url))
+        .collect();
+    pending.sort();
+    pending.dedup();
+    if !pending.is_empty() {
+        let resolved: HashMap<String, String> = source
+            .lookup_upload_urls(&pending)?
+            .into_iter()
+            .map(|lookup| (lookup.short_url, lookup.url))
+            .collect();
+        for short_url in &pending {
+            let Some(url) = resolved.get(short_url) else {
+                eprintln!("Upload not found, leaving link as is: {}", short_url);
+                continue;
+            };
+            let bytes = source.download_upload(url)?;
+            let filename = short_url.trim_start_matches("upload://");
+            let info = target.upload_bytes(filename, bytes, "composer")?;
+            copied.insert(short_url.clone(), info.short_url.unwrap_or(info.url));
+        }
+    }
+
+    let replacements: Vec<(Link, String)> = links
+        .into_iter()
+        .filter_map(|link| {
+            let destination = copied.get(&body[link.range.clone()])?.clone();
+            Some((
+                Link {
+                    label_end: None,
+                    ..link
+                },
+                destination,
+            ))
+        })
+        .collect();
+    Ok(rewrite_links(body, &replacements))
+}
+
+/// Links and images in the body that point at `upload://` short URLs.
+pub(crate) fn upload_links(body: &str) -> Vec<Link> {
+    find_links(body)
+        .into_iter()
+        .filter(|link| body[link.range.clone()].starts_with("upload://"))
+        .collect()
+}
+
+/// A short URL's file part is safe to use as a filename: no separators and
+/// no leading dot.
+fn is_plain_filename(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\'])
+}
+
+/// Directory that relative links in the Markdown file at `path` resolve
+/// against.
+pub(crate) fn markdown_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Resolve a link destination to an existing local file worth uploading.
+/// Remote URLs, anchors, absolute site paths and other Markdown documents
+/// are left alone; plain links only count when the file exists, while a
+/// missing image is reported.
+fn local_file(base_dir: &Path, target: &str, image: bool) -> Option<PathBuf> {
+    let target = target.trim();
+    if target.is_empty()
+        || target.contains("://")
+        || target.starts_with('#')
+        || target.starts_with('/')
+        || target.starts_with("mailto:")
+        || target.starts_with("data:")
+    {
+        return None;
+    }
+    let target = target.split(['#', '?']).next().unwrap_or(target);
+    let path = base_dir.join(target);
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    if !image
+        && matches!(
+            extension.as_deref(),
+            Some("md" | "markdown" | "html" | "htm")
+        )
+    {
+        return None;
+    }
+    if path.is_file() {
+        Some(path)
+    } else {
+        if image {
+            eprintln!("Image not found, leaving link as is: {}", path.display());
+        }
+        None
+    }
+}
+
+/// Replace each link's destination, marking plain links as attachments so
+/// Discourse renders them as downloads.
+pub(crate) fn rewrite_links(body: &str, replacements: &[(Link, String)]) -> String {
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    for (link, destination) in replacements {
+        edits.push((link.range.clone(), destination.clone()));
+        if let Some(label_end) = link.label_end {
+            let label_start = body[..label_end].rfind('[').map_or(0, |i| i + 1);
+            if !body[label_start..label_end].ends_with("|attachment") {
+                edits.push((label_end..label_end, "|attachment".to_string()));
+            }
+        }
+    }
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut out = String::with_capacity(body.len());
+    let mut cursor = 0;
+    for (range, text) in edits {
+        out.push_str(&body[cursor..range.start]);
+        out.push_str(&text);
+        cursor = range.end;
+    }
+    out.push_str(&body[cursor..]);
+    out
+}
+
+/// Find inline Markdown links and images (`[text](dest)`, `![alt](dest)`)
+/// plus `<img src="…">` tags, skipping fenced code blocks.
+pub(crate) fn find_links(body: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut offset = 0;
+    let mut fence: Option<&str> = None;
+    for line in body.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let marker = if trimmed.starts_with("```") {
+            Some("```")
+        } else if trimmed.starts_with("~~~") {
+            Some("~~~")
+        } else {
+            None
+        };
+        match (fence, marker) {
+            (None, Some(marker)) => fence = Some(marker),
+            (Some(open), Some(marker)) if open == marker => fence = None,
+            (None, None) => {
+                find_markdown_links(line, offset, &mut links);
+                find_img_tags(line, offset, &mut links);
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    links.sort_by_key(|link| link.range.start);
+    links
+}
+
+fn find_markdown_links(line: &str, offset: usize, links: &mut Vec<Link>) {
+    let bytes = line.as_bytes();
+    let mut search = 0;
+    while let Some(found) = line[search..].find("](") {
+        let label_end = search + found;
+        let dest_open = label_end + 2;
+        search = dest_open;
+        let Some(label_start) = line[..label_end].rfind('[') else {
+            continue;
+        };
+        let image = label_start > 0 && bytes[label_start - 1] == b'!';
+
+        // Destination: `<…>` or a run without spaces, with balanced parens.
+        let range = if bytes.get(dest_open) == Some(&b'<') {
+            match line[dest_open + 1..].find('>') {
+                Some(close) => dest_open + 1..dest_open + 1 + close,
+                None => continue,
+            }
+        } else {
+            let mut depth = 0usize;
+            let mut end = None;
+            for (i, ch) in line[dest_open..].char_indices() {
+                match ch {
+                    '(' => depth += 1,
+                    ')' if depth == 0 => {
+                        end = Some(dest_open + i);
+                        break;
+                    }
+                    ')' => depth -= 1,
+                    ' ' | '\t' | '\n' => {
+                        end = Some(dest_open + i);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            match end {
+                Some(end) => dest_open..end,
+                None => continue,
+            }
+        };
+        search = range.end;
+        links.push(Link {
+            range: offset + range.start..offset + range.end,
+            label_end: (!image).then_some(offset + label_end),
+            image,
+        });
+    }
+}
+
+fn find_img_tags(line: &str, offset: usize, links: &mut Vec<Link>) {
+    let lower = line.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(found) = lower[search..].find("<img") {
+        let tag_start = search + found;
+        let tag_end = lower[tag_start..]
+            .find('>')
+            .map_or(line.len(), |i| tag_start + i);
+        search = tag_end;
+        let Some(src) = lower[tag_start..tag_end].find("src=") else {
+            continue;
+        };
+        let value_start = tag_start + src + 4;
+        let Some(quote) = line[value_start..]
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+        else {
+            continue;
+        };
+        let Some(close) = line[value_start + 1..tag_end].find(quote) else {
+            continue;
+        };
+        links.push(Link {
+            range: offset + value_start + 1..offset + value_start + 1 + close,
+            label_end: None,
+            image: true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn destinations(body: &str) -> Vec<&str> {
+        find_links(body)
+            .into_iter()
+            .map(|link| &body[link.range])
+            .collect()
+    }
+
+    #[test]
+    fn finds_images_links_and_img_tags() {
+        let body = "![diagram](./img/a.png \"Title\") and [spec](files/spec.pdf)\n\
+                    <img src=\"img/b.jpg\" width=\"10\">\n";
+        assert_eq!(
+            destinations(body),
+            vec!["./img/a.png", "files/spec.pdf", "img/b.jpg"]
+        );
+        let links = find_links(body);
+        assert!(links[0].image);
+        assert!(!links[1].image);
+        assert!(links[1].label_end.is_some());
+    }
+
+    #[test]
+    fn skips_fenced_code_blocks() {
+        let body = "```\n![x](in-code.png)\n```\n![y](outside.png)\n";
+        assert_eq!(destinations(body), vec!["outside.png"]);
+    }
+
+    #[test]
+    fn handles_angle_brackets_and_parens() {
+        let body = "![a](<my image.png>) [b](wiki/Foo_(bar).pdf)";
+        assert_eq!(
+            destinations(body),
+            vec!["my image.png", "wiki/Foo_(bar).pdf"]
+        );
+    }
+
+    #[test]
+    fn rewrite_marks_plain_links_as_attachments() {
+        let body = "![a](img/a.png) [report](report.pdf)";
+        let links = find_links(body);
+        let replacements = vec![
+            (links[0].clone(), "upload://aaa.png".to_string()),
+            (links[1].clone(), "upload://bbb.pdf".to_string()),
+        ];
+        assert_eq!(
+            rewrite_links(body, &replacements),
+            "![a](upload://aaa.png) [report|attachment](upload://bbb.pdf)"
+        );
+    }
+
+    #[test]
+    fn local_file_ignores_remote_and_markdown_targets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.png"), b"png").unwrap();
+        fs::write(dir.path().join("other.md"), b"# doc").unwrap();
+        assert!(local_file(dir.path(), "a.png", true).is_some());
+        assert!(local_file(dir.path(), "https://example.com/a.png", true).is_none());
+        assert!(local_file(dir.path(), "other.md", false).is_none());
+        assert!(local_file(dir.path(), "missing.pdf", false).is_none());
+    }
+
+    #[test]
+    fn short_url_filenames_must_be_plain() {
+        assert!(is_plain_filename("a1b2c3.png"));
+        assert!(!is_plain_filename("../escape.png"));
+        assert!(!is_plain_filename("nested/file.png"));
+        assert!(!is_plain_filename(""));
+    }
+
+    #[test]
+    fn restore_points_known_uploads_back_at_local_files() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("assets")).unwrap();
+        fs::write(dir.path().join("assets").join("a.png"), b"png").unwrap();
+        let mut cache = UploadCache::load(dir.path()).unwrap();
+        cache.insert(
+            "myforum",
+            content_hash(&dir.path().join("assets").join("a.png")).unwrap(),
+            CachedUpload {
+                short_url: "upload://aaa.png".to_string(),
+                url: None,
+                filename: None,
+            },
+        );
+        cache.save().unwrap();
+
+        let local = "![a](assets/a.png)\n";
+        let mapped = map_uploaded_assets("myforum", dir.path(), local).unwrap();
+        assert_eq!(mapped, "![a](upload://aaa.png)\n");
+        let pulled = "Intro\n\n![a](upload://aaa.png) ![b](upload://bbb.png)\n";
+        assert_eq!(
+            restore_local_assets("myforum", dir.path(), local, pulled).unwrap(),
+            "Intro\n\n![a](assets/a.png) ![b](upload://bbb.png)\n"
+        );
+    }
+
+    #[test]
+    fn cache_roundtrips_per_discourse() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = UploadCache::load(dir.path()).unwrap();
+        let upload = CachedUpload {
+            short_url: "upload://aaa.png".to_string(),
+            url: None,
+            filename: None,
+        };
+        cache.insert("myforum", "hash".to_string(), upload.clone());
+        cache.save().unwrap();
+        let cache = UploadCache::load(dir.path()).unwrap();
+        assert_eq!(cache.get("myforum", "hash"), Some(&upload));
+        assert_eq!(cache.get("other", "hash"), None);
+    }
+}
@@ -1,11 +1,30 @@
-use crate::api::{CategoryInfo, DiscourseClient, TopicSummary};
-use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, not_found, select_discourse};
+use crate::api::{
+    CategoryInfo, CategoryPermission, DiscourseClient, Post, TopicSummary, TopicUpdate,
+};
+use crate::cli::{CategoryFields, ListFormat, RemovedTopicAction};
+use crate::commands::assets::{
+    ASSETS_DIR, PullAssets, content_hash, markdown_dir, upload_local_assets,
+};
+use crate::commands::category_spec::{
+    CategoryFile, CategorySpec, NOTIFICATION_SETTINGS, NotificationLevel, format_category_ids,
+    notification_levels, parse_category_ids, parse_permission,
+};
+use crate::commands::common::{
+    confirm_action, ensure_api_credentials, not_found, select_discourse,
+};
+use crate::commands::manifest::{Manifest, ManifestEntry, manifest_key};
+use crate::commands::topic::{
+    SyncBase, localise_posts, render_thread, topic_front_matter, topic_update_from_front_matter,
+    write_post_files, write_sync_base,
+};
 use crate::config::Config;
-use crate::utils::{ensure_dir, normalize_baseurl, read_markdown, slugify, write_markdown};
+use crate::frontmatter::{FrontMatter, read_topic_file, render_front_matter};
+use crate::utils::{ensure_dir, normalize_baseurl, slugify, write_markdown};
 use anyhow::{Context, Result, anyhow};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn category_list(
     config: &Config,
@@ -62,11 +81,27 @@ pub fn category_list(
     Ok(())
 }
 
+/// Groups every Discourse has, whether or not `/groups.json` lists them.
+const AUTOMATIC_GROUPS: &[&str] = &[
+    "everyone",
+    "admins",
+    "moderators",
+    "staff",
+    "trust_level_0",
+    "trust_level_1",
+    "trust_level_2",
+    "trust_level_3",
+    "trust_level_4",
+];
+
+#[allow(clippy::too_many_arguments)]
 pub fn category_copy(
     config: &Config,
     source: &str,
     target: Option<&str>,
     category: &str,
+    deep: bool,
+    keep_name: bool,
     dry_run: bool,
 ) -> Result<()> {
     let source_discourse = select_discourse(config, Some(source))?;
@@ -74,6 +109,12 @@ pub fn category_copy(
     let target_discourse = select_discourse(config, Some(target_name))?;
     ensure_api_credentials(source_discourse)?;
     ensure_api_credentials(target_discourse)?;
+    let cross_forum = target_discourse.name != source_discourse.name;
+    if keep_name && !cross_forum {
+        return Err(anyhow!(
+            "--keep-name only applies when copying to another Discourse (--target)"
+        ));
+    }
     let source_client = DiscourseClient::new(source_discourse)?;
     let category_id = resolve_category_id(&source_client, category)?;
     let categories = source_client.fetch_categories()?;
@@ -82,9 +123,38 @@ pub fn category_copy(
         .find(|cat| cat.id == Some(category_id))
         .ok_or_else(|| not_found("category", category_id))?;
     let mut copied = category.clone();
-    copied.name = format!("Copy of {}", category.name);
-    copied.slug = format!("{}-copy", category.slug);
+    if !keep_name {
+        copied.name = format!("Copy of {}", category.name);
+        copied.slug = format!("{}-copy", category.slug);
+    }
     copied.id = None;
+    if cross_forum {
+        // Parent IDs only mean something on the source forum.
+        copied.parent_category_id = None;
+    }
+    let target_client = DiscourseClient::new(target_discourse)?;
+    if keep_name {
+        let existing = category_tree(&target_client)?;
+        if let Some(clash) = name_clash(&existing, &copied) {
+            return Err(anyhow!(
+                "{} already has a category \"{}\" (slug: {}); copy without --keep-name",
+                target_discourse.name,
+                clash.name,
+                clash.slug
+            ));
+        }
+    }
+    if deep {
+        let copy = DeepCopy {
+            source: &source_client,
+            target: &target_client,
+            target_name: &target_discourse.name,
+            tree: category_tree(&source_client)?,
+            target_groups: target_group_names(&target_client)?,
+            dry_run,
+        };
+        return copy.copy(category_id, copied, 0);
+    }
     if dry_run {
         println!(
             "[dry-run] would create category \"{}\" (slug: {}) on {}",
@@ -92,7 +162,6 @@ pub fn category_copy(
         );
         return Ok(());
     }
-    let target_client = DiscourseClient::new(target_discourse)?;
     let new_id = target_client.create_category(&copied)?;
     let url = format!(
         "{}/c/{}",
@@ -103,17 +172,936 @@ pub fn category_copy(
     Ok(())
 }
 
+/// A category on the target that a kept name or slug would collide with:
+/// same parent, and the same name or slug.
+fn name_clash<'a>(existing: &'a [CategoryInfo], copied: &CategoryInfo) -> Option<&'a CategoryInfo> {
+    existing.iter().find(|category| {
+        category.parent_category_id == copied.parent_category_id
+            && (category.slug == copied.slug || category.name.eq_ignore_ascii_case(&copied.name))
+    })
+}
+
+fn target_group_names(client: &DiscourseClient) -> Result<HashSet<String>> {
+    let mut names: HashSet<String> = client
+        .fetch_groups()?
+        .into_iter()
+        .map(|group| group.name)
+        .collect();
+    names.extend(AUTOMATIC_GROUPS.iter().map(|name| name.to_string()));
+    Ok(names)
+}
+
+/// State for `category copy --deep`, which carries settings, permissions,
+/// the description and every subcategory across.
+struct DeepCopy<'a> {
+    source: &'a DiscourseClient,
+    target: &'a DiscourseClient,
+    target_name: &'a str,
+    /// Every source category, flattened.
+    tree: Vec<CategoryInfo>,
+    target_groups: HashSet<String>,
+    dry_run: bool,
+}
+
+impl DeepCopy<'_> {
+    /// Copy `source_id` as `copied` (name, slug and parent already set), then
+    /// its subcategories under the new category.
+    fn copy(&self, source_id: u64, copied: CategoryInfo, depth: usize) -> Result<()> {
+        let detail = self.source.fetch_category_detail(source_id)?;
+        let (group_permissions, dropped) =
+            map_permissions(&detail.group_permissions, &self.target_groups);
+        for group in dropped {
+            eprintln!(
+                "Group {} not found on {}; leaving it out of \"{}\"",
+                group, self.target_name, copied.name
+            );
+        }
+        let copied = CategoryInfo {
+            name: copied.name,
+            slug: copied.slug,
+            parent_category_id: copied.parent_category_id,
+            id: None,
+            subcategory_list: Vec::new(),
+            group_permissions,
+            ..detail.clone()
+        };
+        let indent = "  ".repeat(depth);
+        let new_id = if self.dry_run {
+            println!(
+                "[dry-run] {}would create category \"{}\" (slug: {}) on {} with access: {}",
+                indent,
+                copied.name,
+                copied.slug,
+                self.target_name,
+                permissions_summary(&copied.group_permissions)
+            );
+            None
+        } else {
+            let id = self.target.create_category(&copied)?;
+            self.copy_description(&detail, id)?;
+            println!(
+                "{}{}/c/{} \"{}\"",
+                indent,
+                self.target.baseurl(),
+                id,
+                copied.name
+            );
+            Some(id)
+        };
+        for child in child_categories(&self.tree, source_id) {
+            let Some(child_id) = child.id else {
+                continue;
+            };
+            let child_copy = CategoryInfo {
+                name: child.name.clone(),
+                slug: child.slug.clone(),
+                parent_category_id: new_id,
+                ..Default::default()
+            };
+            self.copy(child_id, child_copy, depth + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the new category's "About" post with the source's.
+    fn copy_description(&self, source: &CategoryInfo, new_id: u64) -> Result<()> {
+        let Some(raw) = about_post(self.source, source)?.and_then(|post| post.raw) else {
+            return Ok(());
+        };
+        let created = self.target.fetch_category_detail(new_id)?;
+        if let Some(post) = about_post(self.target, &created)? {
+            self.target.update_post(post.id, &raw)?;
+        }
+        Ok(())
+    }
+}
+
+/// First post of the category's "About" topic, if it has one.
+fn about_post(client: &DiscourseClient, category: &CategoryInfo) -> Result<Option<Post>> {
+    let Some(topic_id) = about_topic_id(category) else {
+        return Ok(None);
+    };
+    let topic = client.fetch_topic(topic_id, true)?;
+    Ok(topic.post_stream.posts.into_iter().next())
+}
+
+fn about_topic_id(category: &CategoryInfo) -> Option<u64> {
+    category
+        .topic_url
+        .as_deref()
+        .and_then(|url| url.rsplit('/').next())
+        .and_then(|id| id.parse().ok())
+}
+
+/// Keep permissions for groups that exist on the target, returning the
+/// names of those dropped. If the source was restricted and no group
+/// survives, the copy is limited to staff rather than left open to everyone.
+fn map_permissions(
+    permissions: &[CategoryPermission],
+    target_groups: &HashSet<String>,
+) -> (Vec<CategoryPermission>, Vec<String>) {
+    let (kept, dropped): (Vec<_>, Vec<_>) = permissions
+        .iter()
+        .cloned()
+        .partition(|permission| target_groups.contains(&permission.group_name));
+    let dropped = dropped
+        .into_iter()
+        .map(|permission| permission.group_name)
+        .collect();
+    if kept.is_empty() && !permissions.is_empty() {
+        let staff_only = vec![CategoryPermission {
+            group_name: "staff".to_string(),
+            permission_type: 1,
+        }];
+        return (staff_only, dropped);
+    }
+    (kept, dropped)
+}
+
+fn permissions_summary(permissions: &[CategoryPermission]) -> String {
+    if permissions.is_empty() {
+        return "everyone".to_string();
+    }
+    permissions
+        .iter()
+        .map(|permission| {
+            let level = match permission.permission_type {
+                1 => "full",
+                2 => "reply",
+                3 => "see",
+                _ => "?",
+            };
+            format!("{} ({})", permission.group_name, level)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn category_export(config: &Config, discourse_name: &str, file: Option<&Path>) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let settings = fetch_notification_settings(&client)?;
+    let live = live_categories(&client, &settings)?;
+    let count = live.len();
+    let export = CategoryFile {
+        categories: live.into_iter().map(|category| category.spec).collect(),
+    };
+    let yaml = export.to_yaml()?;
+    match file {
+        Some(path) => {
+            fs::write(path, yaml).with_context(|| format!("writing {}", path.display()))?;
+            println!("Wrote {} categories to {}", count, path.display());
+        }
+        None => print!("{}", yaml),
+    }
+    Ok(())
+}
+
+pub fn category_apply(
+    config: &Config,
+    discourse_name: &str,
+    file: &Path,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let wanted = CategoryFile::load(file)?;
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let settings = fetch_notification_settings(&client)?;
+    let live = live_categories(&client, &settings)?;
+    let plan = plan_category_apply(&wanted.categories, &live)?;
+
+    println!("Plan for {}:", discourse.name);
+    let mut creates = 0;
+    let mut updates = 0;
+    for step in &plan {
+        match step {
+            ApplyStep::Create(spec) => {
+                creates += 1;
+                let under = spec
+                    .parent
+                    .as_deref()
+                    .map(|parent| format!(" under {}", parent))
+                    .unwrap_or_default();
+                println!("  + create {} (\"{}\"){}", spec.slug, spec.name, under);
+            }
+            ApplyStep::Update { spec, fields, .. } => {
+                updates += 1;
+                println!("  ~ update {}: {}", spec.slug, fields.join(", "));
+            }
+        }
+    }
+    let unchanged = wanted.categories.len() - creates - updates;
+    println!(
+        "{} to create, {} to update, {} unchanged",
+        creates, updates, unchanged
+    );
+    if plan.is_empty() {
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would create {} and update {} categories",
+            discourse.name, creates, updates
+        );
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&format!("apply {} change(s)", plan.len()))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let mut ids = slug_ids(&live);
+    let mut levels = Vec::new();
+    for step in &plan {
+        let (spec, id) = match step {
+            ApplyStep::Create(spec) => {
+                let mut info = spec.apply_to(&CategoryInfo::default())?;
+                info.parent_category_id = resolve_parent(spec, &ids)?;
+                let id = client.create_category(&info)?;
+                if let Some(description) = &spec.description {
+                    let created = client.fetch_category_detail(id)?;
+                    set_description(&client, &created, description)?;
+                }
+                println!("Created {} ({}/c/{})", spec.slug, client.baseurl(), id);
+                ids.insert(spec.slug.clone(), id);
+                (spec, id)
+            }
+            ApplyStep::Update { spec, live, fields } => {
+                let Some(id) = live.info.id else {
+                    continue;
+                };
+                let parent_id = resolve_parent(spec, &ids)?;
+                update_from_spec(&client, &live.info, spec, fields, parent_id)?;
+                println!("Updated {}", spec.slug);
+                (spec, id)
+            }
+        };
+        if let Some(level) = spec.notification_level {
+            levels.push((id, level));
+        }
+    }
+    apply_notification_levels(&client, &settings, &levels)
+}
+
+/// A category on the forum, with the spec `category export` would write
+/// for it.
+struct LiveCategory {
+    info: CategoryInfo,
+    spec: CategorySpec,
+}
+
+enum ApplyStep<'a> {
+    Create(&'a CategorySpec),
+    Update {
+        spec: &'a CategorySpec,
+        live: &'a LiveCategory,
+        fields: Vec<&'static str>,
+    },
+}
+
+fn fetch_notification_settings(
+    client: &DiscourseClient,
+) -> Result<Vec<(NotificationLevel, String)>> {
+    NOTIFICATION_SETTINGS
+        .iter()
+        .map(|(level, setting)| Ok((*level, client.fetch_site_setting(setting)?)))
+        .collect()
+}
+
+/// Every category with its full settings and description. Makes a few
+/// requests per category.
+fn live_categories(
+    client: &DiscourseClient,
+    settings: &[(NotificationLevel, String)],
+) -> Result<Vec<LiveCategory>> {
+    let tree = category_tree(client)?;
+    let levels = notification_levels(settings);
+    let slugs: HashMap<u64, String> = tree
+        .iter()
+        .filter_map(|category| Some((category.id?, category.slug.clone())))
+        .collect();
+    let mut live = Vec::new();
+    for category in &tree {
+        let Some(id) = category.id else {
+            continue;
+        };
+        let detail = client.fetch_category_detail(id)?;
+        let description = about_post(client, &detail)?.and_then(|post| post.raw);
+        let parent = detail
+            .parent_category_id
+            .or(category.parent_category_id)
+            .and_then(|parent_id| slugs.get(&parent_id).cloned());
+        let level = levels
+            .get(&id)
+            .copied()
+            .unwrap_or(NotificationLevel::Default);
+        let spec = CategorySpec::from_live(&detail, parent, description, level);
+        live.push(LiveCategory { info: detail, spec });
+    }
+    Ok(live)
+}
+
+/// Match each wanted category to a live one, by parent and slug or else by
+/// a slug only one live category has, and list what has to change. Steps
+/// come parents first. Live categories missing from `wanted` are left out.
+fn plan_category_apply<'a>(
+    wanted: &'a [CategorySpec],
+    live: &'a [LiveCategory],
+) -> Result<Vec<ApplyStep<'a>>> {
+    let mut seen = HashSet::new();
+    for spec in wanted {
+        if !seen.insert((spec.parent.as_deref(), spec.slug.as_str())) {
+            return Err(anyhow!("category {} is listed more than once", spec.slug));
+        }
+    }
+    let mut steps = Vec::new();
+    for spec in wanted {
+        if let Some(parent) = &spec.parent {
+            let known = wanted.iter().any(|other| &other.slug == parent)
+                || live.iter().any(|other| &other.spec.slug == parent);
+            if !known {
+                return Err(anyhow!(
+                    "parent {} of category {} is neither on the forum nor in the file",
+                    parent,
+                    spec.slug
+                ));
+            }
+        }
+        match find_live(spec, live) {
+            Some(existing) => {
+                let fields = spec.changed_fields(&existing.spec);
+                if !fields.is_empty() {
+                    steps.push(ApplyStep::Update {
+                        spec,
+                        live: existing,
+                        fields,
+                    });
+                }
+            }
+            None => {
+                spec.apply_to(&CategoryInfo::default())?;
+                steps.push(ApplyStep::Create(spec));
+            }
+        }
+    }
+    steps.sort_by_key(|step| match step {
+        ApplyStep::Create(spec) | ApplyStep::Update { spec, .. } => spec_depth(spec, wanted),
+    });
+    Ok(steps)
+}
+
+fn find_live<'a>(spec: &CategorySpec, live: &'a [LiveCategory]) -> Option<&'a LiveCategory> {
+    if let Some(exact) = live
+        .iter()
+        .find(|category| category.spec.slug == spec.slug && category.spec.parent == spec.parent)
+    {
+        return Some(exact);
+    }
+    let mut same_slug = live
+        .iter()
+        .filter(|category| category.spec.slug == spec.slug);
+    match (same_slug.next(), same_slug.next()) {
+        (Some(only), None) => Some(only),
+        _ => None,
+    }
+}
+
+/// How many parents up the file's own categories go above `spec`.
+fn spec_depth(spec: &CategorySpec, wanted: &[CategorySpec]) -> usize {
+    let mut depth = 0;
+    let mut parent = spec.parent.as_deref();
+    while let Some(slug) = parent
+        && depth < wanted.len()
+    {
+        depth += 1;
+        parent = wanted
+            .iter()
+            .find(|other| other.slug == slug)
+            .and_then(|other| other.parent.as_deref());
+    }
+    depth
+}
+
+/// Slug -> ID, preferring top-level categories when a slug is reused
+/// under different parents.
+fn slug_ids(live: &[LiveCategory]) -> HashMap<String, u64> {
+    let mut ordered: Vec<&LiveCategory> = live.iter().collect();
+    ordered.sort_by_key(|category| category.spec.parent.is_none());
+    ordered
+        .into_iter()
+        .filter_map(|category| Some((category.spec.slug.clone(), category.info.id?)))
+        .collect()
+}
+
+fn resolve_parent(spec: &CategorySpec, ids: &HashMap<String, u64>) -> Result<Option<u64>> {
+    let Some(parent) = &spec.parent else {
+        return Ok(None);
+    };
+    ids.get(parent)
+        .copied()
+        .map(Some)
+        .ok_or_else(|| not_found("parent category", parent))
+}
+
+/// Write the `fields` of `spec` that changed over `live`, the category's
+/// full settings.
+fn update_from_spec(
+    client: &DiscourseClient,
+    live: &CategoryInfo,
+    spec: &CategorySpec,
+    fields: &[&str],
+    parent_id: Option<u64>,
+) -> Result<()> {
+    let id = live
+        .id
+        .ok_or_else(|| anyhow!("category {} has no ID", live.slug))?;
+    let settings_changed = fields
+        .iter()
+        .any(|field| !matches!(*field, "description" | "notification_level"));
+    if settings_changed {
+        let mut info = spec.apply_to(live)?;
+        info.parent_category_id = parent_id;
+        client.update_category(id, &info)?;
+    }
+    if fields.contains(&"description")
+        && let Some(description) = &spec.description
+    {
+        set_description(client, live, description)?;
+    }
+    Ok(())
+}
+
+fn set_description(client: &DiscourseClient, category: &CategoryInfo, raw: &str) -> Result<()> {
+    match about_post(client, category)? {
+        Some(post) => client.update_post(post.id, raw),
+        None => {
+            eprintln!(
+                "Category {} has no About topic; skipping its description",
+                category.slug
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Move each category in `levels` into the matching
+/// `default_categories_*` setting, writing only the settings that change.
+fn apply_notification_levels(
+    client: &DiscourseClient,
+    settings: &[(NotificationLevel, String)],
+    levels: &[(u64, NotificationLevel)],
+) -> Result<()> {
+    for (level, setting) in NOTIFICATION_SETTINGS {
+        let Some((_, current)) = settings.iter().find(|(current, _)| current == level) else {
+            continue;
+        };
+        let mut ids: Vec<u64> = parse_category_ids(current)
+            .into_iter()
+            .filter(|id| !levels.iter().any(|(changed, _)| changed == id))
+            .collect();
+        ids.extend(
+            levels
+                .iter()
+                .filter(|(_, wanted)| wanted == level)
+                .map(|(id, _)| *id),
+        );
+        let value = format_category_ids(&ids);
+        if value != format_category_ids(&parse_category_ids(current)) {
+            client.update_site_setting(setting, &value)?;
+            println!("Updated {}", setting);
+        }
+    }
+    Ok(())
+}
+
+pub fn category_edit(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    fields: &CategoryFields,
+    dry_run: bool,
+) -> Result<()> {
+    if !has_edits(fields) {
+        return Err(anyhow!(
+            "nothing to change; pass at least one setting such as --name or --color"
+        ));
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = resolve_category_id(&client, category)?;
+    let detail = client.fetch_category_detail(category_id)?;
+    let description = match fields.description {
+        Some(_) => about_post(&client, &detail)?.and_then(|post| post.raw),
+        None => None,
+    };
+    let live = CategorySpec::from_live(&detail, None, description, NotificationLevel::Default);
+    let wanted = edited_spec(&live, fields)?;
+    let changed = wanted.changed_fields(&live);
+    if changed.is_empty() {
+        println!("Category {} already matches", detail.slug);
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would update {} of category {}",
+            discourse.name,
+            changed.join(", "),
+            detail.slug
+        );
+        return Ok(());
+    }
+    update_from_spec(
+        &client,
+        &detail,
+        &wanted,
+        &changed,
+        detail.parent_category_id,
+    )?;
+    println!("Category {} updated: {}", wanted.slug, changed.join(", "));
+    Ok(())
+}
+
+fn has_edits(fields: &CategoryFields) -> bool {
+    fields.name.is_some()
+        || fields.slug.is_some()
+        || fields.color.is_some()
+        || fields.text_color.is_some()
+        || fields.description.is_some()
+        || fields.topic_template.is_some()
+        || fields.default_view.is_some()
+        || fields.allowed_tags.is_some()
+        || fields.allowed_tag_groups.is_some()
+        || fields.minimum_required_tags.is_some()
+        || !fields.permissions.is_empty()
+}
+
+/// `live` with the settings given on the command line.
+fn edited_spec(live: &CategorySpec, fields: &CategoryFields) -> Result<CategorySpec> {
+    let mut wanted = live.clone();
+    if let Some(name) = &fields.name {
+        wanted.name = name.trim().to_string();
+    }
+    if let Some(slug) = &fields.slug {
+        wanted.slug = slug.trim().to_string();
+    }
+    if let Some(color) = &fields.color {
+        wanted.color = Some(color.trim().trim_start_matches('#').to_string());
+    }
+    if let Some(text_color) = &fields.text_color {
+        wanted.text_color = Some(text_color.trim().trim_start_matches('#').to_string());
+    }
+    if fields.description.is_some() {
+        wanted.description = fields.description.clone();
+    }
+    if fields.topic_template.is_some() {
+        wanted.topic_template = fields.topic_template.clone();
+    }
+    if fields.default_view.is_some() {
+        wanted.default_view = fields.default_view.clone();
+    }
+    if let Some(tags) = &fields.allowed_tags {
+        wanted.allowed_tags = Some(trimmed_names(tags));
+    }
+    if let Some(groups) = &fields.allowed_tag_groups {
+        wanted.allowed_tag_groups = Some(trimmed_names(groups));
+    }
+    if fields.minimum_required_tags.is_some() {
+        wanted.minimum_required_tags = fields.minimum_required_tags;
+    }
+    if !fields.permissions.is_empty() {
+        let permissions = fields
+            .permissions
+            .iter()
+            .map(|raw| parse_permission(raw))
+            .collect::<Result<_>>()?;
+        wanted.permissions = Some(permissions);
+    }
+    if wanted.name.is_empty() || wanted.slug.is_empty() {
+        return Err(anyhow!("category name and slug cannot be empty"));
+    }
+    Ok(wanted)
+}
+
+fn trimmed_names(names: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn category_move(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    parent: Option<&str>,
+    top_level: bool,
+    position: Option<i64>,
+    dry_run: bool,
+) -> Result<()> {
+    if parent.is_none() && !top_level && position.is_none() {
+        return Err(anyhow!(
+            "nothing to change; pass --parent, --top-level or --position"
+        ));
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let tree = category_tree(&client)?;
+    let moved = find_category(&tree, category)?;
+    let category_id = moved.id.ok_or_else(|| not_found("category", category))?;
+    let parent_id = match parent {
+        Some(parent) => {
+            let parent = find_category(&tree, parent)?;
+            let parent_id = parent
+                .id
+                .ok_or_else(|| not_found("category", &parent.slug))?;
+            check_category_move(&tree, category_id, parent_id, max_category_nesting(&client))?;
+            Some(parent_id)
+        }
+        None if top_level => None,
+        None => moved.parent_category_id,
+    };
+    let parent_label = parent_id
+        .and_then(|id| tree.iter().find(|category| category.id == Some(id)))
+        .map(|category| category.slug.as_str())
+        .unwrap_or("the top level");
+    if position.is_some() {
+        warn_unless_fixed_positions(&client);
+    }
+    if dry_run {
+        let at = position
+            .map(|position| format!(" at position {}", position))
+            .unwrap_or_default();
+        println!(
+            "[dry-run] {}: would move category {} to {}{}",
+            discourse.name, moved.slug, parent_label, at
+        );
+        return Ok(());
+    }
+    let mut info = client.fetch_category_detail(category_id)?;
+    info.subcategory_list = Vec::new();
+    info.parent_category_id = parent_id;
+    if position.is_some() {
+        info.position = position;
+    }
+    client.update_category(category_id, &info)?;
+    println!("Category {} moved to {}", moved.slug, parent_label);
+    Ok(())
+}
+
+pub fn category_reorder(
+    config: &Config,
+    discourse_name: &str,
+    order: &[String],
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let tree = category_tree(&client)?;
+    let listed = order
+        .iter()
+        .map(|category| find_category(&tree, category))
+        .collect::<Result<Vec<_>>>()?;
+    let positions = reordered_positions(&tree, &listed)?;
+    warn_unless_fixed_positions(&client);
+    if dry_run {
+        println!(
+            "[dry-run] {}: would set category positions:",
+            discourse.name
+        );
+        for (id, position) in &positions {
+            if let Some(category) = tree.iter().find(|category| category.id == Some(*id)) {
+                println!("  {} {}", position, category.slug);
+            }
+        }
+        return Ok(());
+    }
+    client.reorder_categories(&positions)?;
+    println!("Reordered {} categories", positions.len());
+    Ok(())
+}
+
+/// Positions for every category: `listed` first in the order given, then
+/// the rest in their current order.
+fn reordered_positions(tree: &[CategoryInfo], listed: &[&CategoryInfo]) -> Result<Vec<(u64, i64)>> {
+    let mut seen = HashSet::new();
+    for category in listed {
+        if !seen.insert(category.id) {
+            return Err(anyhow!(
+                "category {} is listed more than once",
+                category.slug
+            ));
+        }
+    }
+    let mut rest: Vec<&CategoryInfo> = tree
+        .iter()
+        .filter(|category| !seen.contains(&category.id))
+        .collect();
+    rest.sort_by_key(|category| (category.position.unwrap_or(i64::MAX), category.id));
+    Ok(listed
+        .iter()
+        .chain(rest.iter())
+        .filter_map(|category| category.id)
+        .zip(0..)
+        .collect())
+}
+
+/// Discourse's default `max_category_nesting`: categories and one level of
+/// subcategories.
+const DEFAULT_MAX_CATEGORY_NESTING: usize = 2;
+
+fn max_category_nesting(client: &DiscourseClient) -> usize {
+    client
+        .fetch_site_setting("max_category_nesting")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_MAX_CATEGORY_NESTING)
+}
+
+/// Refuse to put `category_id` under `parent_id` when the parent is the
+/// category itself or one of its descendants, or when the category and its
+/// subcategories would end up nested more than `max_nesting` levels deep.
+fn check_category_move(
+    tree: &[CategoryInfo],
+    category_id: u64,
+    parent_id: u64,
+    max_nesting: usize,
+) -> Result<()> {
+    if parent_id == category_id {
+        return Err(anyhow!("a category cannot be its own parent"));
+    }
+    // Levels from the top down to the new parent, inclusive.
+    let mut parent_depth = 0;
+    let mut ancestor = Some(parent_id);
+    while let Some(id) = ancestor {
+        if id == category_id {
+            return Err(anyhow!(
+                "a category cannot be moved under one of its own subcategories"
+            ));
+        }
+        parent_depth += 1;
+        if parent_depth > tree.len() {
+            break;
+        }
+        ancestor = tree
+            .iter()
+            .find(|category| category.id == Some(id))
+            .and_then(|category| category.parent_category_id);
+    }
+    let depth = parent_depth + subtree_levels(tree, category_id);
+    if depth > max_nesting {
+        return Err(anyhow!(
+            "moving it there would nest categories {} levels deep; max_category_nesting is {}",
+            depth,
+            max_nesting
+        ));
+    }
+    Ok(())
+}
+
+/// Levels in the subtree rooted at `category_id`: 1 for a category with no
+/// subcategories.
+fn subtree_levels(tree: &[CategoryInfo], category_id: u64) -> usize {
+    1 + child_categories(tree, category_id)
+        .into_iter()
+        .filter_map(|child| child.id)
+        .map(|id| subtree_levels(tree, id))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Positions are ignored unless `fixed_category_positions` is on.
+fn warn_unless_fixed_positions(client: &DiscourseClient) {
+    if client
+        .fetch_site_setting("fixed_category_positions")
+        .is_ok_and(|value| value != "true")
+    {
+        eprintln!(
+            "Note: fixed_category_positions is off, so Discourse orders categories by activity \
+             and ignores positions until it is enabled"
+        );
+    }
+}
+
+pub fn category_delete(
+    config: &Config,
+    discourse_name: &str,
+    category: &str,
+    force: bool,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let tree = category_tree(&client)?;
+    let target = find_category(&tree, category)?;
+    let category_id = target.id.ok_or_else(|| not_found("category", category))?;
+    let children = child_categories(&tree, category_id);
+    if !children.is_empty() {
+        let slugs: Vec<&str> = children.iter().map(|child| child.slug.as_str()).collect();
+        return Err(anyhow!(
+            "category {} has subcategories ({}); delete or move them first",
+            target.slug,
+            slugs.join(", ")
+        ));
+    }
+    let detail = client.fetch_category_detail(category_id)?;
+    let about_topic = about_topic_id(&detail);
+    let topics: Vec<TopicSummary> = client
+        .fetch_category(category_id, false)?
+        .topic_list
+        .topics
+        .into_iter()
+        .filter(|topic| Some(topic.id) != about_topic)
+        .collect();
+    if !topics.is_empty() && !force {
+        return Err(anyhow!(
+            "category {} has {} topic(s); pass --force to delete them with it",
+            target.slug,
+            topics.len()
+        ));
+    }
+    let action = if topics.is_empty() {
+        format!("delete category {}", target.slug)
+    } else {
+        format!(
+            "delete category {} and its {} topic(s)",
+            target.slug,
+            topics.len()
+        )
+    };
+    if dry_run {
+        println!("[dry-run] {}: would {}", discourse.name, action);
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&action)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    for topic in &topics {
+        client.delete_topic(topic.id)?;
+    }
+    client.delete_category(category_id)?;
+    println!("Category {} deleted", target.slug);
+    Ok(())
+}
+
+/// A category by ID or slug. A slug used under several parents picks the
+/// top-level one, or fails if none is top-level.
+fn find_category<'a>(tree: &'a [CategoryInfo], category: &str) -> Result<&'a CategoryInfo> {
+    let category = category.trim();
+    if let Ok(id) = category.parse::<u64>() {
+        return tree
+            .iter()
+            .find(|candidate| candidate.id == Some(id))
+            .ok_or_else(|| not_found("category", id));
+    }
+    let matches: Vec<&CategoryInfo> = tree
+        .iter()
+        .filter(|candidate| candidate.slug == category)
+        .collect();
+    match matches.as_slice() {
+        [] => Err(not_found("category", category)),
+        [only] => Ok(only),
+        _ => matches
+            .iter()
+            .find(|candidate| candidate.parent_category_id.is_none())
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "slug {} is used by more than one subcategory; pass the category ID",
+                    category
+                )
+            }),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn category_pull(
     config: &Config,
     discourse_name: &str,
     category: &str,
     local_path: Option<&Path>,
+    include_subcategories: bool,
+    recursive: bool,
+    all_posts: bool,
+    split: bool,
+    download_uploads: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
+    let assets = PullAssets::new(&client, &discourse.name, download_uploads);
     let category_id = resolve_category_id(&client, category)?;
-    let category = client.fetch_category(category_id)?;
+    let category = client.fetch_category(category_id, include_subcategories)?;
     let dir = match local_path {
         Some(path) => path.to_path_buf(),
         None => {
@@ -125,66 +1113,541 @@ pub fn category_pull(
             std::env::current_dir()?.join(name)
         }
     };
-    ensure_dir(&dir)?;
-    for topic in category.topic_list.topics {
-        let topic_detail = client.fetch_topic(topic.id, true)?;
+    let categories = if recursive {
+        category_tree(&client)?
+    } else {
+        Vec::new()
+    };
+    let ctx = PullContext {
+        client: &client,
+        discourse_name: &discourse.name,
+        slugs: category_slugs(&client)?,
+        categories,
+        assets,
+        all_posts,
+        split,
+    };
+    pull_category_dir(&ctx, category_id, category.topic_list.topics, &dir)?;
+    println!("{}", dir.display());
+    Ok(())
+}
+
+/// Settings shared by every directory of a `category pull`.
+struct PullContext<'a> {
+    client: &'a DiscourseClient,
+    discourse_name: &'a str,
+    slugs: HashMap<u64, String>,
+    /// Every category, for `--recursive`; empty otherwise.
+    categories: Vec<CategoryInfo>,
+    assets: PullAssets<'a>,
+    all_posts: bool,
+    split: bool,
+}
+
+/// Write `topics` into `dir`, then each subcategory of `category_id` into
+/// a directory named after its slug.
+fn pull_category_dir(
+    ctx: &PullContext,
+    category_id: u64,
+    topics: Vec<TopicSummary>,
+    dir: &Path,
+) -> Result<()> {
+    ensure_dir(dir)?;
+    let bar = ProgressBar::new(topics.len() as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    for topic in topics {
+        bar.set_message(topic.title.clone());
+        bar.inc(1);
+        let topic_detail = ctx.client.fetch_topic(topic.id, true)?;
+        let front_matter = topic_front_matter(ctx.discourse_name, &topic_detail, &ctx.slugs);
+        if ctx.all_posts {
+            let posts = ctx.client.fetch_topic_posts(&topic_detail)?;
+            if ctx.split {
+                let topic_dir = dir.join(slugify(&topic.title));
+                write_post_files(&topic_dir, &front_matter, &posts, &ctx.assets)?;
+            } else {
+                let path = dir.join(format!("{}.md", slugify(&topic.title)));
+                let posts = localise_posts(&ctx.assets, dir, posts)?;
+                write_markdown(&path, &render_thread(&front_matter, &posts)?)?;
+            }
+            continue;
+        }
         let raw = topic_detail
             .post_stream
             .posts
-            .get(0)
+            .first()
             .and_then(|p| p.raw.clone())
             .unwrap_or_default();
-        let filename = format!("{}.md", slugify(&topic.title));
-        write_markdown(&dir.join(filename), &raw)?;
+        let path = dir.join(format!("{}.md", slugify(&topic.title)));
+        let body = ctx.assets.localise(dir, &raw)?;
+        write_markdown(&path, &render_front_matter(&front_matter, &body)?)?;
+        if let Some(base) = SyncBase::from_topic(&topic_detail) {
+            write_sync_base(&path, &base)?;
+        }
+    }
+    bar.finish_and_clear();
+
+    for sub in child_categories(&ctx.categories, category_id) {
+        let Some(sub_id) = sub.id else { continue };
+        let topics = ctx.client.fetch_category(sub_id, false)?.topic_list.topics;
+        pull_category_dir(ctx, sub_id, topics, &dir.join(&sub.slug))?;
     }
-    println!("{}", dir.display());
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn category_push(
     config: &Config,
     discourse_name: &str,
     category: &str,
     local_path: &Path,
+    include_subcategories: bool,
+    recursive: bool,
+    removed_action: Option<RemovedTopicAction>,
+    dry_run: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
     let category_id = resolve_category_id(&client, category)?;
-    let existing = client.fetch_category(category_id)?;
-    let mut topics = existing.topic_list.topics;
-    let entries =
-        fs::read_dir(local_path).with_context(|| format!("reading {}", local_path.display()))?;
-    for entry in entries {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+    let slugs = category_slugs(&client)?;
+    // Match against every topic, not just the first page, or older topics
+    // would be created again.
+    let mut dirs = vec![CategoryDir {
+        dir: local_path.to_path_buf(),
+        category_id: Some(category_id),
+        topics: client
+            .fetch_category(category_id, include_subcategories)?
+            .topic_list
+            .topics,
+    }];
+    if recursive {
+        let mut categories = category_tree(&client)?;
+        collect_subcategory_dirs(
+            &client,
+            &discourse.name,
+            &mut categories,
+            local_path,
+            Some(category_id),
+            dry_run,
+            &mut dirs,
+        )?;
+    }
+    let mut manifest = Manifest::load(local_path)?;
+    let plan = plan_category_push(local_path, &dirs, &discourse.name, &manifest)?;
+
+    if dry_run {
+        print_push_plan(&discourse.name, &plan, removed_action);
+        return Ok(());
+    }
+
+    let mut unchanged = 0usize;
+    for file in &plan.files {
+        let base_dir = markdown_dir(&file.path);
+        let (topic_id, hash) = match file.topic_id {
+            Some(topic_id) if file.unchanged => {
+                unchanged += 1;
+                (topic_id, file.hash.clone())
+            }
+            Some(topic_id) => {
+                let raw =
+                    upload_local_assets(&client, &discourse.name, &base_dir, &file.raw, false)?;
+                let detail = client.fetch_topic(topic_id, true)?;
+                let post = detail
+                    .post_stream
+                    .posts
+                    .first()
+                    .ok_or_else(|| anyhow!("topic has no posts"))?;
+                client.update_post(post.id, &raw)?;
+                let mut update = match &file.front_matter {
+                    Some(front_matter) => {
+                        topic_update_from_front_matter(front_matter, &detail, &slugs)?
+                    }
+                    None => TopicUpdate::default(),
+                };
+                let title = file.explicit_title.clone().or_else(|| {
+                    file.renamed_from
+                        .as_ref()
+                        .map(|_| file.fallback_title.clone())
+                });
+                if update.title.is_none() {
+                    update.title = title.filter(|t| detail.title.as_deref() != Some(t.as_str()));
+                }
+                // A file moved into another subcategory's directory moves
+                // its topic too.
+                if recursive
+                    && update.category_id.is_none()
+                    && file.category_id != detail.category_id
+                {
+                    update.category_id = file.category_id;
+                }
+                client.update_topic(topic_id, &update)?;
+                match &file.renamed_from {
+                    Some(from) => println!(
+                        "Updated topic {} from {} (renamed from {})",
+                        topic_id, file.key, from
+                    ),
+                    None => println!("Updated topic {} from {}", topic_id, file.key),
+                }
+                (topic_id, file.hash.clone())
+            }
+            None => {
+                let category_id = file
+                    .category_id
+                    .ok_or_else(|| anyhow!("no category for {}", file.key))?;
+                let title = file.title();
+                let raw =
+                    upload_local_assets(&client, &discourse.name, &base_dir, &file.raw, false)?;
+                let topic_id = client.create_topic(category_id, &title, &raw)?;
+                let mut front_matter = file.front_matter.clone().unwrap_or_default();
+                if let Some(tags) = &front_matter.tags {
+                    let update = TopicUpdate {
+                        tags: Some(tags.clone()),
+                        ..TopicUpdate::default()
+                    };
+                    client.update_topic(topic_id, &update)?;
+                }
+                // Record the new topic's identity so the next push updates it
+                // instead of creating another one.
+                front_matter.discourse = Some(discourse.name.clone());
+                front_matter.topic_id = Some(topic_id);
+                front_matter.title = Some(title.clone());
+                front_matter.category = slugs.get(&category_id).cloned();
+                write_markdown(&file.path, &render_front_matter(&front_matter, &file.raw)?)?;
+                println!("Created topic {} from {}", topic_id, file.key);
+                (topic_id, content_hash(&file.path)?)
+            }
+        };
+        manifest.insert(
+            &discourse.name,
+            file.key.clone(),
+            ManifestEntry {
+                topic_id,
+                hash,
+                orphaned: false,
+            },
+        );
+        if let Some(from) = &file.renamed_from {
+            manifest.remove(&discourse.name, from);
+        }
+    }
+    if unchanged > 0 {
+        println!("{} unchanged file(s) skipped", unchanged);
+    }
+
+    if !plan.removed.is_empty() {
+        let prompted = match removed_action {
+            Some(_) => RemovedTopicAction::Keep,
+            None => ask_removed_action(&plan.removed)?,
+        };
+        for (key, entry) in &plan.removed {
+            // Topics orphaned by an earlier push stay that way unless
+            // --removed says otherwise.
+            let action = match removed_action {
+                Some(action) => action,
+                None if entry.orphaned => RemovedTopicAction::Keep,
+                None => prompted,
+            };
+            match action {
+                RemovedTopicAction::Keep => {
+                    // Keep tracking the topic so a later push can still
+                    // close, unlist or delete it.
+                    manifest.insert(
+                        &discourse.name,
+                        key.clone(),
+                        ManifestEntry {
+                            orphaned: true,
+                            ..entry.clone()
+                        },
+                    );
+                    continue;
+                }
+                RemovedTopicAction::Close => {
+                    client.set_topic_status(entry.topic_id, "closed", true, None)?;
+                    println!("Closed topic {} ({} was removed)", entry.topic_id, key);
+                }
+                RemovedTopicAction::Unlist => {
+                    client.set_topic_status(entry.topic_id, "visible", false, None)?;
+                    println!("Unlisted topic {} ({} was removed)", entry.topic_id, key);
+                }
+                RemovedTopicAction::Delete => {
+                    client.delete_topic(entry.topic_id)?;
+                    println!("Deleted topic {} ({} was removed)", entry.topic_id, key);
+                }
+            }
+            manifest.remove(&discourse.name, key);
+        }
+    }
+    manifest.save()
+}
+
+/// What `category push` will do with each file, worked out before anything
+/// is sent.
+struct PushPlan {
+    files: Vec<PlannedFile>,
+    /// Manifest entries whose files are gone and weren't renamed, including
+    /// ones already orphaned by an earlier push.
+    removed: Vec<(String, ManifestEntry)>,
+}
+
+/// A local directory and the category its files are pushed into.
+struct CategoryDir {
+    dir: PathBuf,
+    /// `None` for a subcategory that a dry run would create.
+    category_id: Option<u64>,
+    /// Existing topics to match files against.
+    topics: Vec<TopicSummary>,
+}
+
+struct PlannedFile {
+    path: PathBuf,
+    key: String,
+    /// Category the file belongs to, from its directory.
+    category_id: Option<u64>,
+    front_matter: Option<FrontMatter>,
+    raw: String,
+    hash: String,
+    /// Title from front matter or the `# heading`.
+    explicit_title: Option<String>,
+    /// Title derived from the file name.
+    fallback_title: String,
+    /// Existing topic to update; `None` creates one.
+    topic_id: Option<u64>,
+    /// Manifest key the file had before it was renamed.
+    renamed_from: Option<String>,
+    /// Same topic and content hash as the last push.
+    unchanged: bool,
+}
+
+impl PlannedFile {
+    fn title(&self) -> String {
+        self.explicit_title
+            .clone()
+            .unwrap_or_else(|| self.fallback_title.clone())
+    }
+}
+
+/// Match each Markdown file in `dirs` to a topic: by manifest entry, then
+/// front matter `topic_id`, then as a rename of a vanished manifest entry
+/// with the same content hash, then by title or slug among its directory's
+/// topics. A front matter `topic_id` that belongs to a vanished entry is a
+/// rename too, even when the content was edited. Manifest keys are relative
+/// to `root`.
+fn plan_category_push(
+    root: &Path,
+    dirs: &[CategoryDir],
+    discourse_name: &str,
+    manifest: &Manifest,
+) -> Result<PushPlan> {
+    let known = manifest.files(discourse_name);
+    let mut paths = Vec::new();
+    for category_dir in dirs {
+        let dir = &category_dir.dir;
+        let mut dir_paths = Vec::new();
+        let entries = fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                dir_paths.push(path);
+            }
+        }
+        dir_paths.sort();
+        paths.extend(dir_paths.into_iter().map(|path| (path, category_dir)));
+    }
+    let present: HashSet<String> = paths
+        .iter()
+        .map(|(path, _)| manifest_key(root, path))
+        .collect();
+    let mut vanished: BTreeMap<String, ManifestEntry> = known
+        .iter()
+        .filter(|(key, _)| !present.contains(*key))
+        .map(|(key, entry)| (key.clone(), entry.clone()))
+        .collect();
+
+    let mut files = Vec::new();
+    for (path, category_dir) in paths {
+        let key = manifest_key(root, &path);
+        let (front_matter, raw) = read_topic_file(&path)?;
+        if let Some(Err(err)) = front_matter.as_ref().map(|fm| fm.ensure_pushable(&path)) {
+            eprintln!("Skipping {}", err);
             continue;
         }
-        let raw = read_markdown(&path)?;
-        let title = extract_title(&raw)
-            .unwrap_or_else(|| path.file_stem().unwrap().to_string_lossy().to_string());
-        if let Some(topic) = find_topic_match(&topics, &title, &path) {
-            let detail = client.fetch_topic(topic.id, true)?;
-            let post = detail
-                .post_stream
-                .posts
-                .get(0)
-                .ok_or_else(|| anyhow!("topic has no posts"))?;
-            client.update_post(post.id, &raw)?;
+        let hash = content_hash(&path)?;
+        let explicit_title = front_matter
+            .as_ref()
+            .and_then(|fm| fm.title.clone())
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| extract_title(&raw));
+        let fallback_title = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| key.clone());
+        let title = explicit_title
+            .clone()
+            .unwrap_or_else(|| fallback_title.clone());
+
+        let mut renamed_from = None;
+        let topic_id = if let Some(entry) = known.get(&key) {
+            Some(entry.topic_id)
+        } else if let Some(id) = front_matter
+            .as_ref()
+            .and_then(|fm| fm.topic_id_for(discourse_name))
+        {
+            renamed_from = vanished
+                .iter()
+                .find(|(_, entry)| entry.topic_id == id)
+                .map(|(from, _)| from.clone());
+            if let Some(from) = &renamed_from {
+                vanished.remove(from);
+            }
+            Some(id)
+        } else if let Some(from) = vanished
+            .iter()
+            .find(|(_, entry)| entry.hash == hash)
+            .map(|(from, _)| from.clone())
+        {
+            let entry = vanished.remove(&from);
+            renamed_from = Some(from);
+            entry.map(|entry| entry.topic_id)
         } else {
-            let topic_id = client.create_topic(category_id, &title, &raw)?;
-            topics.push(TopicSummary {
-                id: topic_id,
-                title: title.clone(),
-                slug: slugify(&title),
-            });
+            find_topic_match(&category_dir.topics, &title, &path).map(|t| t.id)
+        };
+        let unchanged = known
+            .get(&key)
+            .is_some_and(|entry| entry.hash == hash && Some(entry.topic_id) == topic_id);
+        files.push(PlannedFile {
+            path,
+            key,
+            category_id: category_dir.category_id,
+            front_matter,
+            raw,
+            hash,
+            explicit_title,
+            fallback_title,
+            topic_id,
+            renamed_from,
+            unchanged,
+        });
+    }
+
+    // A vanished entry whose topic another file now points at (say, one
+    // matched by title) was moved, not removed.
+    let claimed: HashSet<u64> = files.iter().filter_map(|file| file.topic_id).collect();
+    for file in files.iter_mut() {
+        if file.renamed_from.is_none() && !known.contains_key(&file.key) {
+            file.renamed_from = vanished
+                .iter()
+                .find(|(_, entry)| Some(entry.topic_id) == file.topic_id)
+                .map(|(from, _)| from.clone());
         }
     }
-    Ok(())
+    let removed = vanished
+        .into_iter()
+        .filter(|(_, entry)| !claimed.contains(&entry.topic_id))
+        .collect();
+    Ok(PushPlan { files, removed })
+}
+
+fn print_push_plan(
+    discourse_name: &str,
+    plan: &PushPlan,
+    removed_action: Option<RemovedTopicAction>,
+) {
+    let mut unchanged = 0usize;
+    for file in &plan.files {
+        match (file.topic_id, &file.renamed_from) {
+            (Some(_), _) if file.unchanged => unchanged += 1,
+            (Some(topic_id), Some(from)) => println!(
+                "[dry-run] {}: would update topic {} from {} (renamed from {})",
+                discourse_name, topic_id, file.key, from
+            ),
+            (Some(topic_id), None) => println!(
+                "[dry-run] {}: would update topic {} from {}",
+                discourse_name, topic_id, file.key
+            ),
+            (None, _) => println!(
+                "[dry-run] {}: would create topic \"{}\" from {}",
+                discourse_name,
+                file.title(),
+                file.key
+            ),
+        }
+    }
+    if unchanged > 0 {
+        println!(
+            "[dry-run] {}: {} unchanged file(s) would be skipped",
+            discourse_name, unchanged
+        );
+    }
+    for (key, entry) in &plan.removed {
+        match removed_action {
+            Some(RemovedTopicAction::Keep) => println!(
+                "[dry-run] {}: would keep topic {} ({} was removed)",
+                discourse_name, entry.topic_id, key
+            ),
+            Some(action) => println!(
+                "[dry-run] {}: would {} topic {} ({} was removed)",
+                discourse_name,
+                removed_action_verb(action),
+                entry.topic_id,
+                key
+            ),
+            None if entry.orphaned => println!(
+                "[dry-run] {}: topic {} stays orphaned ({} was removed)",
+                discourse_name, entry.topic_id, key
+            ),
+            None => println!(
+                "[dry-run] {}: topic {} has no file ({} was removed); pass --removed to close, unlist or delete it",
+                discourse_name, entry.topic_id, key
+            ),
+        }
+    }
+}
+
+fn removed_action_verb(action: RemovedTopicAction) -> &'static str {
+    match action {
+        RemovedTopicAction::Keep => "keep",
+        RemovedTopicAction::Close => "close",
+        RemovedTopicAction::Unlist => "unlist",
+        RemovedTopicAction::Delete => "delete",
+    }
+}
+
+/// List the topics whose files are gone and offer to close the newly
+/// removed ones. Declining keeps them, flagged as orphaned.
+fn ask_removed_action(removed: &[(String, ManifestEntry)]) -> Result<RemovedTopicAction> {
+    println!("{} topic(s) no longer have a file:", removed.len());
+    for (key, entry) in removed {
+        let note = if entry.orphaned { ", orphaned" } else { "" };
+        println!("  topic {} ({}{})", entry.topic_id, key, note);
+    }
+    let new = removed.iter().filter(|(_, entry)| !entry.orphaned).count();
+    if new == 0 {
+        return Ok(RemovedTopicAction::Keep);
+    }
+    if confirm_action(&format!("close {} topic(s)", new))? {
+        return Ok(RemovedTopicAction::Close);
+    }
+    println!("Keeping them as orphaned; pass --removed to close, unlist or delete them later.");
+    Ok(RemovedTopicAction::Keep)
+}
+
+/// Map every category ID (subcategories included) to its slug.
+pub(crate) fn category_slugs(client: &DiscourseClient) -> Result<HashMap<u64, String>> {
+    let mut flat = Vec::new();
+    for category in client.fetch_categories()? {
+        flatten_categories(&category, &mut flat);
+    }
+    Ok(flat
+        .into_iter()
+        .filter_map(|c| c.id.map(|id| (id, c.slug)))
+        .collect())
 }
 
-fn resolve_category_id(client: &DiscourseClient, category: &str) -> Result<u64> {
+pub(crate) fn resolve_category_id(client: &DiscourseClient, category: &str) -> Result<u64> {
     if let Ok(id) = category.parse::<u64>() {
         return Ok(id);
     }
@@ -194,21 +1657,169 @@ fn resolve_category_id(client: &DiscourseClient, category: &str) -> Result<u64>
             "missing category identifier for category operation"
         ));
     }
-    let categories = client.fetch_categories()?;
-    let category = categories
-        .into_iter()
-        .find(|cat| cat.slug == slug)
-        .ok_or_else(|| not_found("category", slug))?;
+    let tree = category_tree(client)?;
+    let category = find_category(&tree, slug)?;
     category.id.ok_or_else(|| not_found("category", slug))
 }
 
 fn flatten_categories(category: &CategoryInfo, out: &mut Vec<CategoryInfo>) {
     out.push(category.clone());
     for sub in &category.subcategory_list {
-        flatten_categories(sub, out);
+        if sub.parent_category_id.is_none() && category.id.is_some() {
+            let mut sub = sub.clone();
+            sub.parent_category_id = category.id;
+            flatten_categories(&sub, out);
+        } else {
+            flatten_categories(sub, out);
+        }
     }
 }
 
+/// Every category once, flattened, with parents filled in from nesting.
+fn category_tree(client: &DiscourseClient) -> Result<Vec<CategoryInfo>> {
+    let mut flat = Vec::new();
+    for category in client.fetch_categories()? {
+        flatten_categories(&category, &mut flat);
+    }
+    Ok(unique_categories(flat))
+}
+
+fn child_categories(categories: &[CategoryInfo], parent_id: u64) -> Vec<&CategoryInfo> {
+    categories
+        .iter()
+        .filter(|category| category.parent_category_id == Some(parent_id))
+        .collect()
+}
+
+/// Add a `CategoryDir` for each subdirectory of `dir`, matched by slug to a
+/// subcategory of `parent_id`, creating the subcategory when it is missing.
+/// Recurses into every subdirectory it adds.
+fn collect_subcategory_dirs(
+    client: &DiscourseClient,
+    discourse_name: &str,
+    categories: &mut Vec<CategoryInfo>,
+    dir: &Path,
+    parent_id: Option<u64>,
+    dry_run: bool,
+    out: &mut Vec<CategoryDir>,
+) -> Result<()> {
+    let mut subdirs = Vec::new();
+    let entries = fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() && !skip_push_dir(&path)? {
+            subdirs.push(path);
+        }
+    }
+    subdirs.sort();
+    for sub_dir in subdirs {
+        let slug = sub_dir
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let existing = parent_id.and_then(|parent_id| {
+            child_categories(categories, parent_id)
+                .into_iter()
+                .find(|category| category.slug == slug)
+                .and_then(|category| category.id)
+        });
+        let (category_id, topics) = match (existing, parent_id) {
+            (Some(id), _) => (
+                Some(id),
+                client.fetch_category(id, false)?.topic_list.topics,
+            ),
+            (None, _) if dry_run => {
+                println!(
+                    "[dry-run] {}: would create subcategory \"{}\" from {}",
+                    discourse_name,
+                    category_name_from_dir(&slug),
+                    sub_dir.display()
+                );
+                (None, Vec::new())
+            }
+            (None, None) => return Err(anyhow!("no parent category for {}", sub_dir.display())),
+            (None, Some(parent_id)) => {
+                let parent = categories.iter().find(|c| c.id == Some(parent_id));
+                let category = CategoryInfo {
+                    name: category_name_from_dir(&slug),
+                    slug: slug.clone(),
+                    color: parent
+                        .and_then(|p| p.color.clone())
+                        .or_else(|| Some("0088CC".to_string())),
+                    text_color: parent
+                        .and_then(|p| p.text_color.clone())
+                        .or_else(|| Some("FFFFFF".to_string())),
+                    parent_category_id: Some(parent_id),
+                    ..Default::default()
+                };
+                let id = client.create_category(&category)?;
+                println!("Created subcategory {} from {}", id, sub_dir.display());
+                categories.push(CategoryInfo {
+                    id: Some(id),
+                    ..category
+                });
+                (Some(id), Vec::new())
+            }
+        };
+        out.push(CategoryDir {
+            dir: sub_dir.clone(),
+            category_id,
+            topics,
+        });
+        collect_subcategory_dirs(
+            client,
+            discourse_name,
+            categories,
+            &sub_dir,
+            category_id,
+            dry_run,
+            out,
+        )?;
+    }
+    Ok(())
+}
+
+/// Directories `category push --recursive` leaves alone: hidden ones,
+/// downloaded assets, and `--split` topic directories (their files carry
+/// a `post_number`).
+fn skip_push_dir(path: &Path) -> Result<bool> {
+    let name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if name.starts_with('.') || name == ASSETS_DIR {
+        return Ok(true);
+    }
+    let entries = fs::read_dir(path).with_context(|| format!("reading {}", path.display()))?;
+    for entry in entries {
+        let file = entry?.path();
+        if file.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+        let (front_matter, _) = read_topic_file(&file)?;
+        if front_matter.is_some_and(|fm| fm.post_number.is_some()) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Name for a subcategory created from a directory: `getting-started`
+/// becomes "Getting Started".
+fn category_name_from_dir(slug: &str) -> String {
+    slug.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
 fn unique_categories(flat: Vec<CategoryInfo>) -> Vec<CategoryInfo> {
     let mut seen = std::collections::HashSet::new();
     let mut unique = Vec::new();
@@ -327,3 +1938,333 @@ fn find_topic_match<'a>(
                 .unwrap_or(false)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CategoryDir, category_name_from_dir, check_category_move, edited_spec, find_category,
+        map_permissions, name_clash, plan_category_push, reordered_positions, skip_push_dir,
+    };
+    use crate::api::{CategoryInfo, CategoryPermission};
+    use crate::cli::CategoryFields;
+    use crate::commands::assets::content_hash;
+    use crate::commands::category_spec::{CategorySpec, NotificationLevel};
+    use crate::commands::manifest::{Manifest, ManifestEntry};
+    use std::fs;
+    use std::path::Path;
+
+    fn category_dir(dir: &Path, category_id: u64) -> CategoryDir {
+        CategoryDir {
+            dir: dir.to_path_buf(),
+            category_id: Some(category_id),
+            topics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn plan_detects_unchanged_renamed_new_and_removed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("same.md"), "# Same\n\nbody\n").unwrap();
+        fs::write(root.join("renamed.md"), "# Moved\n\nbody\n").unwrap();
+        fs::write(root.join("new.md"), "# New\n\nbody\n").unwrap();
+
+        let mut manifest = Manifest::load(root).unwrap();
+        let entry = |topic_id, path: &str| ManifestEntry {
+            topic_id,
+            hash: content_hash(&root.join(path)).unwrap(),
+            orphaned: false,
+        };
+        manifest.insert("myforum", "same.md".to_string(), entry(1, "same.md"));
+        manifest.insert("myforum", "old-name.md".to_string(), entry(2, "renamed.md"));
+        manifest.insert(
+            "myforum",
+            "deleted.md".to_string(),
+            ManifestEntry {
+                topic_id: 3,
+                hash: "gone".to_string(),
+                orphaned: false,
+            },
+        );
+
+        let plan =
+            plan_category_push(root, &[category_dir(root, 1)], "myforum", &manifest).unwrap();
+        let file = |key: &str| plan.files.iter().find(|f| f.key == key).unwrap();
+        assert!(file("same.md").unchanged);
+        assert_eq!(file("renamed.md").topic_id, Some(2));
+        assert_eq!(
+            file("renamed.md").renamed_from.as_deref(),
+            Some("old-name.md")
+        );
+        assert_eq!(file("new.md").topic_id, None);
+        assert_eq!(file("new.md").title(), "New");
+        assert_eq!(plan.removed.len(), 1);
+        assert_eq!(plan.removed[0].0, "deleted.md");
+        assert_eq!(plan.removed[0].1.topic_id, 3);
+    }
+
+    #[test]
+    fn plan_treats_edited_manifest_file_as_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("doc.md"), "# Doc\n\nedited\n").unwrap();
+        let mut manifest = Manifest::load(root).unwrap();
+        manifest.insert(
+            "myforum",
+            "doc.md".to_string(),
+            ManifestEntry {
+                topic_id: 9,
+                hash: "stale".to_string(),
+                orphaned: false,
+            },
+        );
+        let plan =
+            plan_category_push(root, &[category_dir(root, 1)], "myforum", &manifest).unwrap();
+        assert_eq!(plan.files[0].topic_id, Some(9));
+        assert!(!plan.files[0].unchanged);
+        assert!(plan.removed.is_empty());
+    }
+
+    #[test]
+    fn plan_matches_renamed_and_edited_file_by_front_matter() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join("renamed.md"),
+            "---\ntopic_id: 7\n---\n# Doc\n\nedited\n",
+        )
+        .unwrap();
+        let mut manifest = Manifest::load(root).unwrap();
+        manifest.insert(
+            "myforum",
+            "old-name.md".to_string(),
+            ManifestEntry {
+                topic_id: 7,
+                hash: "before the edit".to_string(),
+                orphaned: false,
+            },
+        );
+        let plan =
+            plan_category_push(root, &[category_dir(root, 1)], "myforum", &manifest).unwrap();
+        assert_eq!(plan.files[0].topic_id, Some(7));
+        assert_eq!(plan.files[0].renamed_from.as_deref(), Some("old-name.md"));
+        assert!(plan.removed.is_empty());
+    }
+
+    #[test]
+    fn plan_keeps_reporting_orphaned_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let mut manifest = Manifest::load(root).unwrap();
+        manifest.insert(
+            "myforum",
+            "kept.md".to_string(),
+            ManifestEntry {
+                topic_id: 8,
+                hash: "gone".to_string(),
+                orphaned: true,
+            },
+        );
+        let plan =
+            plan_category_push(root, &[category_dir(root, 1)], "myforum", &manifest).unwrap();
+        assert!(plan.files.is_empty());
+        assert_eq!(plan.removed.len(), 1);
+        assert!(plan.removed[0].1.orphaned);
+    }
+
+    #[test]
+    fn plan_follows_files_moved_between_category_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let sub = root.join("guides");
+        fs::create_dir(&sub).unwrap();
+        fs::write(sub.join("intro.md"), "# Intro\n\nbody\n").unwrap();
+        fs::write(sub.join("new.md"), "# New\n\nbody\n").unwrap();
+        let mut manifest = Manifest::load(root).unwrap();
+        manifest.insert(
+            "myforum",
+            "intro.md".to_string(),
+            ManifestEntry {
+                topic_id: 4,
+                hash: content_hash(&sub.join("intro.md")).unwrap(),
+                orphaned: false,
+            },
+        );
+
+        let dirs = [category_dir(root, 1), category_dir(&sub, 2)];
+        let plan = plan_category_push(root, &dirs, "myforum", &manifest).unwrap();
+        let file = |key: &str| plan.files.iter().find(|f| f.key == key).unwrap();
+        assert_eq!(file("guides/intro.md").topic_id, Some(4));
+        assert_eq!(
+            file("guides/intro.md").renamed_from.as_deref(),
+            Some("intro.md")
+        );
+        assert_eq!(file("guides/intro.md").category_id, Some(2));
+        assert_eq!(file("guides/new.md").category_id, Some(2));
+        assert!(plan.removed.is_empty());
+    }
+
+    #[test]
+    fn recursive_push_skips_assets_hidden_and_split_topic_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for name in ["assets", ".git", "thread", "guides"] {
+            fs::create_dir(root.join(name)).unwrap();
+        }
+        fs::write(
+            root.join("thread").join("001-alice.md"),
+            "---\ntopic_id: 3\npost_number: 1\n---\nbody\n",
+        )
+        .unwrap();
+        fs::write(root.join("guides").join("intro.md"), "# Intro\n").unwrap();
+        assert!(skip_push_dir(&root.join("assets")).unwrap());
+        assert!(skip_push_dir(&root.join(".git")).unwrap());
+        assert!(skip_push_dir(&root.join("thread")).unwrap());
+        assert!(!skip_push_dir(&root.join("guides")).unwrap());
+    }
+
+    #[test]
+    fn category_name_from_dir_title_cases_words() {
+        assert_eq!(category_name_from_dir("getting-started"), "Getting Started");
+        assert_eq!(category_name_from_dir("faq"), "Faq");
+    }
+
+    fn permission(group: &str, permission_type: u64) -> CategoryPermission {
+        CategoryPermission {
+            group_name: group.to_string(),
+            permission_type,
+        }
+    }
+
+    #[test]
+    fn deep_copy_keeps_permissions_for_groups_on_the_target() {
+        let target_groups = ["staff", "editors"].map(String::from).into();
+        let (kept, dropped) = map_permissions(
+            &[permission("editors", 1), permission("partners", 3)],
+            &target_groups,
+        );
+        assert_eq!(kept, vec![permission("editors", 1)]);
+        assert_eq!(dropped, vec!["partners".to_string()]);
+
+        // A restricted category never ends up open to everyone.
+        let (kept, _) = map_permissions(&[permission("partners", 1)], &target_groups);
+        assert_eq!(kept, vec![permission("staff", 1)]);
+        let (kept, dropped) = map_permissions(&[], &target_groups);
+        assert!(kept.is_empty() && dropped.is_empty());
+    }
+
+    #[test]
+    fn kept_names_clash_with_siblings_only() {
+        let existing = vec![CategoryInfo {
+            name: "Support".to_string(),
+            slug: "support".to_string(),
+            parent_category_id: Some(4),
+            ..Default::default()
+        }];
+        let copied = |name: &str, slug: &str, parent| CategoryInfo {
+            name: name.to_string(),
+            slug: slug.to_string(),
+            parent_category_id: parent,
+            ..Default::default()
+        };
+        assert!(name_clash(&existing, &copied("Support", "help", Some(4))).is_some());
+        assert!(name_clash(&existing, &copied("Help", "support", Some(4))).is_some());
+        assert!(name_clash(&existing, &copied("Support", "support", None)).is_none());
+    }
+
+    fn category(id: u64, slug: &str, parent: Option<u64>, position: i64) -> CategoryInfo {
+        CategoryInfo {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            id: Some(id),
+            parent_category_id: parent,
+            position: Some(position),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reorder_puts_listed_categories_first_and_keeps_the_rest_in_order() {
+        let tree = vec![
+            category(1, "general", None, 0),
+            category(2, "support", None, 1),
+            category(3, "meta", None, 2),
+            category(4, "staff", None, 3),
+        ];
+        let listed = vec![&tree[3], &tree[1]];
+        assert_eq!(
+            reordered_positions(&tree, &listed).unwrap(),
+            vec![(4, 0), (2, 1), (1, 2), (3, 3)]
+        );
+        assert!(reordered_positions(&tree, &[&tree[0], &tree[0]]).is_err());
+    }
+
+    #[test]
+    fn move_rejects_cycles_and_nesting_past_the_limit() {
+        let tree = vec![
+            category(1, "docs", None, 0),
+            category(2, "guides", Some(1), 0),
+            category(3, "faq", Some(2), 0),
+            category(4, "meta", None, 1),
+        ];
+        assert!(check_category_move(&tree, 1, 1, 3).is_err());
+        assert!(check_category_move(&tree, 1, 3, 3).is_err());
+        assert!(check_category_move(&tree, 1, 2, 9).is_err());
+        // meta under guides: three levels, allowed only with nesting 3.
+        assert!(check_category_move(&tree, 4, 2, 3).is_ok());
+        assert!(check_category_move(&tree, 4, 2, 2).is_err());
+        // docs brings its two levels of subcategories along.
+        assert!(check_category_move(&tree, 1, 4, 3).is_err());
+        assert!(check_category_move(&tree, 2, 4, 3).is_ok());
+    }
+
+    #[test]
+    fn find_category_prefers_ids_then_top_level_slugs() {
+        let tree = vec![
+            category(1, "help", None, 0),
+            category(2, "docs", None, 1),
+            category(3, "help", Some(2), 0),
+            category(4, "faq", Some(2), 1),
+            category(5, "faq", Some(1), 0),
+        ];
+        assert_eq!(find_category(&tree, "3").unwrap().id, Some(3));
+        assert_eq!(find_category(&tree, "help").unwrap().id, Some(1));
+        assert_eq!(find_category(&tree, "docs").unwrap().id, Some(2));
+        assert!(find_category(&tree, "faq").is_err());
+        assert!(find_category(&tree, "missing").is_err());
+    }
+
+    #[test]
+    fn edit_changes_only_the_given_settings() {
+        let live = CategorySpec::from_live(
+            &category(1, "support", None, 0),
+            None,
+            None,
+            NotificationLevel::Default,
+        );
+        let fields = CategoryFields {
+            name: Some("Help".to_string()),
+            slug: None,
+            color: Some("#FF0000".to_string()),
+            text_color: None,
+            description: None,
+            topic_template: None,
+            default_view: None,
+            allowed_tags: Some(vec![" a ".to_string(), String::new()]),
+            allowed_tag_groups: None,
+            minimum_required_tags: None,
+            permissions: vec!["staff=full".to_string()],
+        };
+        let wanted = edited_spec(&live, &fields).unwrap();
+        assert_eq!(wanted.color.as_deref(), Some("FF0000"));
+        assert_eq!(
+            wanted.changed_fields(&live),
+            vec!["name", "color", "permissions", "allowed_tags"]
+        );
+        let bad = CategoryFields {
+            permissions: vec!["staff=owner".to_string()],
+            ..fields
+        };
+        assert!(edited_spec(&live, &bad).is_err());
+    }
+}
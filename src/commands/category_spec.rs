@@ -0,0 +1,425 @@
+use crate::api::{CategoryInfo, CategoryPermission, RequiredTagGroup};
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+/// What `category export` writes and `category apply` reads.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CategoryFile {
+    pub categories: Vec<CategorySpec>,
+}
+
+impl CategoryFile {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let raw =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_yaml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub(crate) fn to_yaml(&self) -> Result<String> {
+        serde_yaml::to_string(self).context("serialising category file")
+    }
+}
+
+/// A category's editable settings. Apart from the name and slug, a field
+/// left out is left alone by `category apply`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct CategorySpec {
+    pub name: String,
+    pub slug: String,
+    /// Parent category slug; absent for a top-level category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_color: Option<String>,
+    /// Raw Markdown of the category's "About" post.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Group name -> access level. Empty means everyone, full access.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<BTreeMap<String, PermissionLevel>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic_template: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_view: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tags: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tag_groups: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minimum_required_tags: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_tag_groups: Option<Vec<RequiredTagGroup>>,
+    /// Notification level new users start with for the category.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notification_level: Option<NotificationLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum PermissionLevel {
+    /// Create, reply and see.
+    Full,
+    /// Reply and see.
+    Reply,
+    See,
+}
+
+impl PermissionLevel {
+    fn from_type(permission_type: u64) -> Option<Self> {
+        match permission_type {
+            1 => Some(Self::Full),
+            2 => Some(Self::Reply),
+            3 => Some(Self::See),
+            _ => None,
+        }
+    }
+
+    fn permission_type(self) -> u64 {
+        match self {
+            Self::Full => 1,
+            Self::Reply => 2,
+            Self::See => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum NotificationLevel {
+    /// In none of the `default_categories_*` settings.
+    Default,
+    Watching,
+    Tracking,
+    WatchingFirstPost,
+    Normal,
+    Muted,
+}
+
+/// The site setting listing the categories each level applies to.
+pub(crate) const NOTIFICATION_SETTINGS: &[(NotificationLevel, &str)] = &[
+    (NotificationLevel::Watching, "default_categories_watching"),
+    (NotificationLevel::Tracking, "default_categories_tracking"),
+    (
+        NotificationLevel::WatchingFirstPost,
+        "default_categories_watching_first_post",
+    ),
+    (NotificationLevel::Normal, "default_categories_normal"),
+    (NotificationLevel::Muted, "default_categories_muted"),
+];
+
+/// Category IDs in a `default_categories_*` value (`12|34`).
+pub(crate) fn parse_category_ids(value: &str) -> Vec<u64> {
+    value
+        .split('|')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
+}
+
+pub(crate) fn format_category_ids(ids: &[u64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+impl CategorySpec {
+    /// The spec `category export` writes for a live category.
+    pub(crate) fn from_live(
+        info: &CategoryInfo,
+        parent: Option<String>,
+        description: Option<String>,
+        notification_level: NotificationLevel,
+    ) -> Self {
+        Self {
+            name: info.name.clone(),
+            slug: info.slug.clone(),
+            parent,
+            position: info.position,
+            color: info.color.clone(),
+            text_color: info.text_color.clone(),
+            description,
+            permissions: Some(permission_map(&info.group_permissions)),
+            topic_template: Some(info.topic_template.clone().unwrap_or_default()),
+            default_view: info.default_view.clone(),
+            allowed_tags: Some(info.allowed_tags.clone()),
+            allowed_tag_groups: Some(info.allowed_tag_groups.clone()),
+            minimum_required_tags: Some(info.minimum_required_tags.unwrap_or(0)),
+            required_tag_groups: Some(info.required_tag_groups.clone()),
+            notification_level: Some(notification_level),
+        }
+    }
+
+    /// Names of the fields this spec sets to something other than `live`.
+    pub(crate) fn changed_fields(&self, live: &CategorySpec) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        if self.name != live.name {
+            changed.push("name");
+        }
+        if self.slug != live.slug {
+            changed.push("slug");
+        }
+        if self.parent != live.parent {
+            changed.push("parent");
+        }
+        let mut check = |name: &'static str, differs: bool| {
+            if differs {
+                changed.push(name);
+            }
+        };
+        check("position", differs(&self.position, &live.position));
+        check("color", differs_ignoring_case(&self.color, &live.color));
+        check(
+            "text_color",
+            differs_ignoring_case(&self.text_color, &live.text_color),
+        );
+        check(
+            "description",
+            self.description.as_ref().is_some_and(|description| {
+                live.description.as_deref().map(str::trim) != Some(description.trim())
+            }),
+        );
+        check(
+            "permissions",
+            self.permissions.as_ref().is_some_and(|permissions| {
+                Some(normalise_permissions(permissions))
+                    != live.permissions.as_ref().map(normalise_permissions)
+            }),
+        );
+        check(
+            "topic_template",
+            differs(&self.topic_template, &live.topic_template),
+        );
+        check(
+            "default_view",
+            differs(&self.default_view, &live.default_view),
+        );
+        check(
+            "allowed_tags",
+            differs(&self.allowed_tags, &live.allowed_tags),
+        );
+        check(
+            "allowed_tag_groups",
+            differs(&self.allowed_tag_groups, &live.allowed_tag_groups),
+        );
+        check(
+            "minimum_required_tags",
+            differs(&self.minimum_required_tags, &live.minimum_required_tags),
+        );
+        check(
+            "required_tag_groups",
+            differs(&self.required_tag_groups, &live.required_tag_groups),
+        );
+        check(
+            "notification_level",
+            differs(&self.notification_level, &live.notification_level),
+        );
+        changed
+    }
+
+    /// `base` with every field this spec sets applied. The parent is left
+    /// to the caller, which resolves the slug to an ID.
+    pub(crate) fn apply_to(&self, base: &CategoryInfo) -> Result<CategoryInfo> {
+        let mut info = base.clone();
+        info.name = self.name.clone();
+        info.slug = self.slug.clone();
+        info.subcategory_list = Vec::new();
+        if self.position.is_some() {
+            info.position = self.position;
+        }
+        if self.color.is_some() {
+            info.color = self.color.clone();
+        }
+        if self.text_color.is_some() {
+            info.text_color = self.text_color.clone();
+        }
+        if let Some(permissions) = &self.permissions {
+            info.group_permissions = permissions
+                .iter()
+                .map(|(group, level)| CategoryPermission {
+                    group_name: group.clone(),
+                    permission_type: level.permission_type(),
+                })
+                .collect();
+        }
+        if self.topic_template.is_some() {
+            info.topic_template = self.topic_template.clone();
+        }
+        if self.default_view.is_some() {
+            info.default_view = self.default_view.clone();
+        }
+        if let Some(tags) = &self.allowed_tags {
+            info.allowed_tags = tags.clone();
+        }
+        if let Some(groups) = &self.allowed_tag_groups {
+            info.allowed_tag_groups = groups.clone();
+        }
+        if self.minimum_required_tags.is_some() {
+            info.minimum_required_tags = self.minimum_required_tags;
+        }
+        if let Some(groups) = &self.required_tag_groups {
+            info.required_tag_groups = groups.clone();
+        }
+        if info.color.is_none() || info.text_color.is_none() {
+            return Err(anyhow!(
+                "category {} needs color and text_color to be created",
+                self.slug
+            ));
+        }
+        Ok(info)
+    }
+}
+
+/// Parse a `group=level` access entry, e.g. `staff=full`.
+pub(crate) fn parse_permission(raw: &str) -> Result<(String, PermissionLevel)> {
+    let (group, level) = raw
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected group=level, got {}", raw))?;
+    let level = match level.trim() {
+        "full" => PermissionLevel::Full,
+        "reply" => PermissionLevel::Reply,
+        "see" => PermissionLevel::See,
+        other => {
+            return Err(anyhow!(
+                "unknown permission level {} for {}; use full, reply or see",
+                other,
+                group.trim()
+            ));
+        }
+    };
+    Ok((group.trim().to_string(), level))
+}
+
+fn differs<T: PartialEq>(wanted: &Option<T>, live: &Option<T>) -> bool {
+    wanted.is_some() && wanted != live
+}
+
+fn differs_ignoring_case(wanted: &Option<String>, live: &Option<String>) -> bool {
+    match (wanted, live) {
+        (Some(wanted), Some(live)) => !wanted.eq_ignore_ascii_case(live),
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+fn permission_map(permissions: &[CategoryPermission]) -> BTreeMap<String, PermissionLevel> {
+    let map: BTreeMap<String, PermissionLevel> = permissions
+        .iter()
+        .filter_map(|permission| {
+            let level = PermissionLevel::from_type(permission.permission_type)?;
+            Some((permission.group_name.clone(), level))
+        })
+        .collect();
+    normalise_permissions(&map)
+}
+
+/// No permissions and `everyone: full` mean the same thing.
+fn normalise_permissions(
+    permissions: &BTreeMap<String, PermissionLevel>,
+) -> BTreeMap<String, PermissionLevel> {
+    if permissions.is_empty() {
+        return BTreeMap::from([("everyone".to_string(), PermissionLevel::Full)]);
+    }
+    permissions.clone()
+}
+
+/// Each category's default notification level, from the
+/// `default_categories_*` setting values.
+pub(crate) fn notification_levels(
+    settings: &[(NotificationLevel, String)],
+) -> HashMap<u64, NotificationLevel> {
+    let mut levels = HashMap::new();
+    for (level, value) in settings {
+        for id in parse_category_ids(value) {
+            levels.entry(id).or_insert(*level);
+        }
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live_spec() -> CategorySpec {
+        let info = CategoryInfo {
+            name: "Support".to_string(),
+            slug: "support".to_string(),
+            color: Some("0088CC".to_string()),
+            text_color: Some("FFFFFF".to_string()),
+            id: Some(4),
+            position: Some(2),
+            ..Default::default()
+        };
+        CategorySpec::from_live(
+            &info,
+            None,
+            Some("Ask here.".to_string()),
+            NotificationLevel::Default,
+        )
+    }
+
+    #[test]
+    fn exported_spec_matches_itself_after_a_yaml_roundtrip() {
+        let file = CategoryFile {
+            categories: vec![live_spec()],
+        };
+        let parsed: CategoryFile = serde_yaml::from_str(&file.to_yaml().unwrap()).unwrap();
+        assert_eq!(parsed.categories, file.categories);
+        assert!(parsed.categories[0].changed_fields(&live_spec()).is_empty());
+    }
+
+    #[test]
+    fn only_fields_present_in_the_file_are_compared() {
+        let wanted: CategorySpec = serde_yaml::from_str(
+            "name: Help\nslug: support\ncolor: 0088cc\npermissions:\n  staff: full\n",
+        )
+        .unwrap();
+        assert_eq!(
+            wanted.changed_fields(&live_spec()),
+            vec!["name", "permissions"]
+        );
+    }
+
+    #[test]
+    fn empty_permissions_mean_everyone() {
+        let mut wanted = live_spec();
+        wanted.permissions = Some(BTreeMap::new());
+        assert!(wanted.changed_fields(&live_spec()).is_empty());
+    }
+
+    #[test]
+    fn new_categories_need_colours() {
+        let mut wanted = live_spec();
+        assert!(wanted.apply_to(&CategoryInfo::default()).is_ok());
+        wanted.text_color = None;
+        assert!(wanted.apply_to(&CategoryInfo::default()).is_err());
+    }
+
+    #[test]
+    fn parses_group_permission_entries() {
+        assert_eq!(
+            parse_permission("trust_level_1 = reply").unwrap(),
+            ("trust_level_1".to_string(), PermissionLevel::Reply)
+        );
+        assert!(parse_permission("staff").is_err());
+        assert!(parse_permission("staff=admin").is_err());
+    }
+
+    #[test]
+    fn notification_levels_come_from_site_settings() {
+        let levels = notification_levels(&[
+            (NotificationLevel::Watching, "4|7".to_string()),
+            (NotificationLevel::Muted, "9".to_string()),
+        ]);
+        assert_eq!(levels.get(&7), Some(&NotificationLevel::Watching));
+        assert_eq!(levels.get(&9), Some(&NotificationLevel::Muted));
+        assert_eq!(format_category_ids(&parse_category_ids("4| 7|x")), "4|7");
+    }
+}
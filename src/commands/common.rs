@@ -1,7 +1,10 @@
 use crate::api::DiscourseClient;
 use crate::config::{Config, DiscourseConfig, find_discourse};
+use crate::diff::{DiffLine, Hunk};
+use crate::utils::color_text;
 use anyhow::{Context, Result, anyhow};
 use std::fmt::Display;
+use std::io::{self, Write};
 use std::process::Command;
 
 pub fn select_discourse<'a>(
@@ -47,6 +50,35 @@ pub fn parse_tags(raw: &str) -> Vec<String> {
         .collect()
 }
 
+/// Discourses carrying any of the comma/semicolon separated `tags`, or all
+/// of them when no tags are given.
+pub fn discourses_with_tags<'a>(
+    config: &'a Config,
+    tags: Option<&str>,
+) -> Vec<&'a DiscourseConfig> {
+    let filter: Vec<String> = tags
+        .map(parse_tags)
+        .unwrap_or_default()
+        .iter()
+        .map(|tag| tag.to_ascii_lowercase())
+        .collect();
+    config
+        .discourse
+        .iter()
+        .filter(|disc| {
+            if filter.is_empty() {
+                return true;
+            }
+            let Some(disc_tags) = disc.tags.as_ref() else {
+                return false;
+            };
+            disc_tags
+                .iter()
+                .any(|tag| filter.contains(&tag.to_ascii_lowercase()))
+        })
+        .collect()
+}
+
 pub fn fetch_fullname_from_url(baseurl: &str) -> Option<String> {
     let temp = DiscourseConfig {
         name: "temp".to_string(),
@@ -103,6 +135,42 @@ pub fn open_url(url: &str) -> Result<()> {
     }
 }
 
+/// Ask `Proceed to {action}? [y/N]` on stdin.
+pub fn confirm_action(action: &str) -> Result<bool> {
+    print!("Proceed to {}? [y/N]: ", action);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(matches!(input.trim(), "y" | "Y" | "yes" | "YES"))
+}
+
+/// Ask for `expected` to be typed back before going ahead with `action`,
+/// for changes that cannot be undone.
+pub fn confirm_typed(action: &str, expected: &str) -> Result<bool> {
+    print!("This will {}. Type {} to confirm: ", action, expected);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim() == expected)
+}
+
+/// Print `hunks` as a coloured unified diff between `old_label` and
+/// `new_label`.
+pub fn print_unified_diff(old_label: &str, new_label: &str, hunks: &[Hunk]) {
+    println!("{}", color_text(&format!("--- {}", old_label), 1));
+    println!("{}", color_text(&format!("+++ {}", new_label), 1));
+    for hunk in hunks {
+        println!("{}", color_text(&hunk.header(), 36));
+        for line in &hunk.lines {
+            match line {
+                DiffLine::Context(text) => println!(" {}", text),
+                DiffLine::Removed(text) => println!("{}", color_text(&format!("-{}", text), 31)),
+                DiffLine::Added(text) => println!("{}", color_text(&format!("+{}", text), 32)),
+            }
+        }
+    }
+}
+
 /// Parse one-email-per-line input. Ignores blank lines, `#` comments
 /// (full-line and inline), and leading/trailing whitespace. De-duplicates
 /// while preserving the first-seen order, lowercasing as it goes.
@@ -1,12 +1,18 @@
 use crate::api::DiscourseClient;
-use crate::api::GroupSummary;
-use crate::cli::{ListFormat, StructuredFormat};
-use crate::commands::common::{ensure_api_credentials, not_found, parse_emails, select_discourse};
-use crate::config::Config;
+use crate::api::{GroupDetail, GroupMember, GroupSummary};
+use crate::cli::{GroupFields, ListFormat, MatrixFormat, StructuredFormat};
+use crate::commands::common::{
+    confirm_action, discourses_with_tags, ensure_api_credentials, not_found, parse_emails,
+    select_discourse,
+};
+use crate::commands::group_spec::{FieldChange, GroupFile, GroupSpec};
+use crate::config::{Config, DiscourseConfig};
 use crate::utils::{normalize_baseurl, slugify};
 use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 use std::path::Path;
 
 pub fn group_list(
@@ -151,6 +157,199 @@ fn find_group_summary(client: &DiscourseClient, group_id: u64) -> Result<GroupSu
         .ok_or_else(|| not_found("group", group_id))
 }
 
+pub fn group_create(
+    config: &Config,
+    discourse_name: &str,
+    name: &str,
+    fields: &GroupFields,
+    dry_run: bool,
+) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("missing group name"));
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let spec = GroupSpec::from_fields(name.to_string(), fields);
+    if dry_run {
+        println!(
+            "[dry-run] {}: would create group \"{}\"",
+            discourse.name, spec.name
+        );
+        return Ok(());
+    }
+    let client = DiscourseClient::new(discourse)?;
+    let new_id = client.create_group(&spec.apply_to(&GroupDetail::default()))?;
+    println!(
+        "{}/g/{}/{}",
+        normalize_baseurl(&discourse.baseurl),
+        spec.name,
+        new_id
+    );
+    Ok(())
+}
+
+pub fn group_edit(
+    config: &Config,
+    discourse_name: &str,
+    group_id: u64,
+    name: Option<&str>,
+    fields: &GroupFields,
+    dry_run: bool,
+) -> Result<()> {
+    if name.is_none() && GroupSpec::from_fields(String::new(), fields) == GroupSpec::default() {
+        return Err(anyhow!(
+            "nothing to change; pass at least one setting such as --name or --visibility"
+        ));
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let summary = find_group_summary(&client, group_id)?;
+    let detail = client.fetch_group_detail(summary.id, Some(&summary.name))?;
+    let name = name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&detail.name);
+    let wanted = GroupSpec::from_fields(name.to_string(), fields);
+    let changes = wanted.changes(&GroupSpec::from_detail(&detail));
+    if changes.is_empty() {
+        println!("Group {} already matches", detail.name);
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would update group {}:",
+            discourse.name, detail.name
+        );
+        print_field_changes(&changes);
+        return Ok(());
+    }
+    client.update_group(detail.id, &wanted.apply_to(&detail))?;
+    println!("Group {} updated", wanted.name);
+    print_field_changes(&changes);
+    Ok(())
+}
+
+pub fn group_delete(
+    config: &Config,
+    discourse_name: &str,
+    group_id: u64,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let group = find_group_summary(&client, group_id)?;
+    if dry_run {
+        println!(
+            "[dry-run] {}: would delete group {} ({})",
+            discourse.name, group.name, group.id
+        );
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&format!("delete group {}", group.name))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    client.delete_group(group.id)?;
+    println!("Group {} deleted", group.name);
+    Ok(())
+}
+
+pub fn group_apply(
+    config: &Config,
+    discourse_name: &str,
+    file: &Path,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let wanted = GroupFile::load(file)?;
+    let mut seen = HashSet::new();
+    for spec in &wanted.groups {
+        if !seen.insert(spec.name.to_ascii_lowercase()) {
+            return Err(anyhow!("group {} is listed more than once", spec.name));
+        }
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let groups = client.fetch_groups()?;
+
+    let mut steps = Vec::new();
+    for spec in &wanted.groups {
+        let existing = groups
+            .iter()
+            .find(|group| group.name.eq_ignore_ascii_case(&spec.name));
+        match existing {
+            Some(group) => {
+                let detail = client.fetch_group_detail(group.id, Some(&group.name))?;
+                let changes = spec.changes(&GroupSpec::from_detail(&detail));
+                if !changes.is_empty() {
+                    steps.push((spec, Some(detail), changes));
+                }
+            }
+            None => steps.push((spec, None, Vec::new())),
+        }
+    }
+
+    println!("Plan for {}:", discourse.name);
+    for (spec, detail, changes) in &steps {
+        match detail {
+            Some(_) => {
+                println!("  ~ update {}", spec.name);
+                print_field_changes(changes);
+            }
+            None => println!("  + create {}", spec.name),
+        }
+    }
+    let creates = steps
+        .iter()
+        .filter(|(_, detail, _)| detail.is_none())
+        .count();
+    let updates = steps.len() - creates;
+    println!(
+        "{} to create, {} to update, {} unchanged",
+        creates,
+        updates,
+        wanted.groups.len() - steps.len()
+    );
+    if steps.is_empty() {
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would create {} and update {} groups",
+            discourse.name, creates, updates
+        );
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&format!("apply {} change(s)", steps.len()))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    for (spec, detail, _) in &steps {
+        match detail {
+            Some(detail) => {
+                client.update_group(detail.id, &spec.apply_to(detail))?;
+                println!("Updated {}", spec.name);
+            }
+            None => {
+                let id = client.create_group(&spec.apply_to(&GroupDetail::default()))?;
+                println!("Created {} ({})", spec.name, id);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_field_changes(changes: &[FieldChange]) {
+    for change in changes {
+        println!("      {}: {} -> {}", change.field, change.from, change.to);
+    }
+}
+
 pub fn group_add(
     config: &Config,
     discourse_name: &str,
@@ -220,3 +419,662 @@ fn read_email_source(local_path: Option<&Path>) -> Result<String> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn group_sync(
+    config: &Config,
+    discourse_name: &str,
+    group_id: u64,
+    local_path: &Path,
+    no_remove: bool,
+    notify: bool,
+    skip_unknown: bool,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let raw = fs::read_to_string(local_path)
+        .with_context(|| format!("reading {}", local_path.display()))?;
+    let entries = parse_sync_file(&raw)?;
+    if entries.is_empty() {
+        return Err(anyhow!(
+            "no members found in {}; refusing to empty the group",
+            local_path.display()
+        ));
+    }
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let group = find_group_summary(&client, group_id)?;
+    let membership = client.fetch_group_membership(group.id, Some(&group.name))?;
+    let wanted = resolve_sync_entries(&client, entries, skip_unknown)?;
+    let plan = plan_group_sync(&wanted, &membership.members, &membership.owners, !no_remove);
+
+    println!("Group {} on {}:", group.name, discourse.name);
+    print_sync_plan(&plan);
+    if plan.is_empty() {
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would apply these changes to group {}",
+            discourse.name, group.name
+        );
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&format!("apply these changes to group {}", group.name))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let mut errors = Vec::new();
+    let outcome = client.add_group_members_by_username(group.id, &plan.add, notify)?;
+    errors.extend(outcome.errors);
+    let outcome = client.add_group_members_by_email(group.id, &plan.add_by_email, notify)?;
+    errors.extend(outcome.errors);
+    client.add_group_owners(group.id, &plan.promote)?;
+    for owner in &plan.demote {
+        client.remove_group_owner(group.id, owner.id)?;
+    }
+    let removed: Vec<String> = plan
+        .remove
+        .iter()
+        .map(|member| member.username.clone())
+        .collect();
+    client.remove_group_members_by_username(group.id, &removed)?;
+    println!("Group {} synced", group.name);
+    if !errors.is_empty() {
+        eprintln!("Server notes:");
+        for msg in &errors {
+            eprintln!("  - {}", msg);
+        }
+    }
+    Ok(())
+}
+
+/// One line of a `group sync` file.
+#[derive(Debug, Clone, PartialEq)]
+struct SyncEntry {
+    /// Username or email address.
+    login: String,
+    owner: bool,
+}
+
+/// A wanted member. Those listed by email keep the address so they are
+/// added by email.
+#[derive(Debug, Clone, PartialEq)]
+struct WantedMember {
+    username: String,
+    email: Option<String>,
+    owner: bool,
+}
+
+/// Changes `group sync` makes, in the order it makes them.
+#[derive(Debug, Default, PartialEq)]
+struct SyncPlan {
+    add: Vec<String>,
+    add_by_email: Vec<String>,
+    promote: Vec<String>,
+    demote: Vec<GroupMember>,
+    remove: Vec<GroupMember>,
+    /// Members missing from the file that `--no-remove` keeps.
+    kept: usize,
+}
+
+impl SyncPlan {
+    fn is_empty(&self) -> bool {
+        self.add.is_empty()
+            && self.add_by_email.is_empty()
+            && self.promote.is_empty()
+            && self.demote.is_empty()
+            && self.remove.is_empty()
+    }
+}
+
+/// Parse CSV lines of `username-or-email[,owner]`. A header row naming the
+/// first column, blank lines and `#` comments are skipped; `owner` is
+/// `yes`/`true`/`1` or `no`/`false`/`0`/empty.
+fn parse_sync_file(raw: &str) -> Result<Vec<SyncEntry>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .comment(Some(b'#'))
+        .trim(csv::Trim::All)
+        .from_reader(raw.as_bytes());
+    let mut entries: Vec<SyncEntry> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (row, record) in reader.records().enumerate() {
+        let record = record?;
+        let login = record.get(0).unwrap_or("");
+        if login.is_empty() {
+            continue;
+        }
+        let owner = record.get(1).unwrap_or("");
+        if row == 0
+            && matches!(
+                login.to_ascii_lowercase().as_str(),
+                "user" | "username" | "email" | "login" | "member"
+            )
+        {
+            continue;
+        }
+        let owner = match owner.to_ascii_lowercase().as_str() {
+            "" | "no" | "n" | "false" | "0" => false,
+            "yes" | "y" | "true" | "1" | "owner" => true,
+            other => {
+                let line = record.position().map(|pos| pos.line()).unwrap_or(0);
+                return Err(anyhow!(
+                    "line {}: owner column must be yes or no, got {}",
+                    line,
+                    other
+                ));
+            }
+        };
+        let key = login.to_ascii_lowercase();
+        match index.get(&key) {
+            Some(&existing) => entries[existing].owner |= owner,
+            None => {
+                index.insert(key, entries.len());
+                entries.push(SyncEntry {
+                    login: login.to_string(),
+                    owner,
+                });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Look up the account behind each email address. An email with no account
+/// is an error unless `skip_unknown`: dropping it could remove the member it
+/// was meant to keep.
+fn resolve_sync_entries(
+    client: &DiscourseClient,
+    entries: Vec<SyncEntry>,
+    skip_unknown: bool,
+) -> Result<Vec<WantedMember>> {
+    let mut wanted = Vec::with_capacity(entries.len());
+    let mut unknown = Vec::new();
+    for entry in entries {
+        if !entry.login.contains('@') {
+            wanted.push(WantedMember {
+                username: entry.login,
+                email: None,
+                owner: entry.owner,
+            });
+            continue;
+        }
+        let Some(user) = client.find_user_by_email(&entry.login)? else {
+            unknown.push(entry.login);
+            continue;
+        };
+        wanted.push(WantedMember {
+            username: user.username,
+            email: Some(entry.login),
+            owner: entry.owner,
+        });
+    }
+    if !unknown.is_empty() && !skip_unknown {
+        return Err(anyhow!(
+            "no account for {}; fix the file or pass --skip-unknown to sync without them",
+            unknown.join(", ")
+        ));
+    }
+    for email in &unknown {
+        eprintln!("No account for {}; skipping it", email);
+    }
+    Ok(wanted)
+}
+
+/// Compare the wanted members with the group's. Owners appear in both
+/// `members` and `owners` or in `owners` only, depending on the Discourse
+/// version, so both count as members.
+fn plan_group_sync(
+    wanted: &[WantedMember],
+    members: &[GroupMember],
+    owners: &[GroupMember],
+    remove: bool,
+) -> SyncPlan {
+    let key = |username: &str| username.to_ascii_lowercase();
+    let current: HashSet<String> = members
+        .iter()
+        .chain(owners)
+        .map(|member| key(&member.username))
+        .collect();
+    let current_owners: HashSet<String> = owners.iter().map(|owner| key(&owner.username)).collect();
+    let wanted_names: HashSet<String> = wanted.iter().map(|member| key(&member.username)).collect();
+    let wanted_owners: HashSet<String> = wanted
+        .iter()
+        .filter(|member| member.owner)
+        .map(|member| key(&member.username))
+        .collect();
+
+    let mut plan = SyncPlan::default();
+    for member in wanted {
+        let name = key(&member.username);
+        if !current.contains(&name) {
+            match &member.email {
+                Some(email) => plan.add_by_email.push(email.clone()),
+                None => plan.add.push(member.username.clone()),
+            }
+        }
+        if member.owner && !current_owners.contains(&name) {
+            plan.promote.push(member.username.clone());
+        }
+    }
+    let mut seen = HashSet::new();
+    for member in members.iter().chain(owners) {
+        let name = key(&member.username);
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+        if !wanted_names.contains(&name) {
+            if remove {
+                plan.remove.push(member.clone());
+            } else {
+                plan.kept += 1;
+            }
+        } else if current_owners.contains(&name) && !wanted_owners.contains(&name) && remove {
+            plan.demote.push(member.clone());
+        }
+    }
+    plan
+}
+
+fn print_sync_plan(plan: &SyncPlan) {
+    for username in &plan.add {
+        println!("  + {}", username);
+    }
+    for email in &plan.add_by_email {
+        println!("  + {}", email);
+    }
+    for username in &plan.promote {
+        println!("  ^ {} (owner)", username);
+    }
+    for member in &plan.demote {
+        println!("  v {} (owner -> member)", member.username);
+    }
+    for member in &plan.remove {
+        println!("  - {}", member.username);
+    }
+    println!(
+        "{} to add, {} to remove, {} to make owner, {} to demote",
+        plan.add.len() + plan.add_by_email.len(),
+        plan.remove.len(),
+        plan.promote.len(),
+        plan.demote.len()
+    );
+    if plan.kept > 0 {
+        println!(
+            "Keeping {} member(s) not in the file (--no-remove)",
+            plan.kept
+        );
+    }
+}
+
+/// Tabulate group membership across every discourse matching `tags`. A
+/// discourse or group that cannot be read is reported and skipped.
+pub fn group_matrix(config: &Config, tags: Option<&str>, format: MatrixFormat) -> Result<()> {
+    let discourses = discourses_with_tags(config, tags);
+    if discourses.is_empty() {
+        return Err(anyhow!("no discourses matched the tag filter"));
+    }
+    let mut groups = Vec::new();
+    let mut seats = Vec::new();
+    for discourse in discourses {
+        if let Err(err) = collect_memberships(discourse, &mut groups, &mut seats) {
+            eprintln!("{}: skipped: {:#}", discourse.name, err);
+        }
+    }
+    let matrix = build_matrix(&groups, &seats);
+    match format {
+        MatrixFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            write_matrix_csv(&matrix, &mut writer)?;
+            writer.flush()?;
+        }
+        MatrixFormat::Json => {
+            let raw = serde_json::to_string_pretty(&matrix)?;
+            println!("{}", raw);
+        }
+        MatrixFormat::MarkdownTable => print!("{}", render_matrix_markdown(&matrix)),
+    }
+    Ok(())
+}
+
+/// One user's membership of one group on one discourse.
+#[derive(Debug, Clone)]
+struct Seat {
+    discourse: String,
+    group: String,
+    username: String,
+    owner: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct MatrixGroup {
+    name: String,
+    automatic: bool,
+}
+
+/// Where a user holds a group, by discourse.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct MatrixSeat {
+    discourse: String,
+    owner: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MatrixUser {
+    username: String,
+    groups: BTreeMap<String, Vec<MatrixSeat>>,
+}
+
+/// Users by groups. Groups with the same name on different discourses
+/// share a column.
+#[derive(Debug, Serialize)]
+struct GroupMatrix {
+    discourses: Vec<String>,
+    groups: Vec<MatrixGroup>,
+    users: Vec<MatrixUser>,
+}
+
+fn collect_memberships(
+    discourse: &DiscourseConfig,
+    groups: &mut Vec<(String, GroupSummary)>,
+    seats: &mut Vec<Seat>,
+) -> Result<()> {
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    for group in client.fetch_groups()? {
+        // "everyone" (ID 0) has no member list.
+        if group.id == 0 {
+            continue;
+        }
+        let membership = match client.fetch_group_membership(group.id, Some(&group.name)) {
+            Ok(membership) => membership,
+            Err(err) => {
+                eprintln!(
+                    "{}: skipped group {}: {:#}",
+                    discourse.name, group.name, err
+                );
+                continue;
+            }
+        };
+        let owners: HashSet<&str> = membership
+            .owners
+            .iter()
+            .map(|owner| owner.username.as_str())
+            .collect();
+        let mut seen = HashSet::new();
+        for member in membership.members.iter().chain(&membership.owners) {
+            if !seen.insert(member.username.as_str()) {
+                continue;
+            }
+            seats.push(Seat {
+                discourse: discourse.name.clone(),
+                group: group.name.clone(),
+                username: member.username.clone(),
+                owner: owners.contains(member.username.as_str()),
+            });
+        }
+        groups.push((discourse.name.clone(), group));
+    }
+    Ok(())
+}
+
+/// Arrange seats into a matrix: the discourses' own groups alphabetically,
+/// then the automatic ones; users alphabetically, ignoring case.
+fn build_matrix(groups: &[(String, GroupSummary)], seats: &[Seat]) -> GroupMatrix {
+    let mut discourses: Vec<String> = groups
+        .iter()
+        .map(|(discourse, _)| discourse.clone())
+        .collect();
+    discourses.dedup();
+
+    let mut columns: BTreeMap<String, MatrixGroup> = BTreeMap::new();
+    for (_, group) in groups {
+        let column = columns
+            .entry(group.name.to_ascii_lowercase())
+            .or_insert_with(|| MatrixGroup {
+                name: group.name.clone(),
+                automatic: false,
+            });
+        column.automatic |= group.automatic;
+    }
+
+    let mut users: BTreeMap<String, MatrixUser> = BTreeMap::new();
+    for seat in seats {
+        let user = users
+            .entry(seat.username.to_ascii_lowercase())
+            .or_insert_with(|| MatrixUser {
+                username: seat.username.clone(),
+                groups: BTreeMap::new(),
+            });
+        let group = columns
+            .get(&seat.group.to_ascii_lowercase())
+            .map(|column| column.name.clone())
+            .unwrap_or_else(|| seat.group.clone());
+        user.groups.entry(group).or_default().push(MatrixSeat {
+            discourse: seat.discourse.clone(),
+            owner: seat.owner,
+        });
+    }
+
+    let mut groups: Vec<MatrixGroup> = columns.into_values().collect();
+    groups.sort_by_key(|group| group.automatic);
+    GroupMatrix {
+        discourses,
+        groups,
+        users: users.into_values().collect(),
+    }
+}
+
+impl GroupMatrix {
+    fn header(&self) -> Vec<String> {
+        let mut header = vec!["username".to_string()];
+        header.extend(self.groups.iter().map(|group| {
+            if group.automatic {
+                format!("{} (auto)", group.name)
+            } else {
+                group.name.clone()
+            }
+        }));
+        header
+    }
+
+    /// A user's row. With one discourse a cell is `x` or `owner`; with more
+    /// it lists the discourses, marking those where the user is an owner.
+    fn row(&self, user: &MatrixUser) -> Vec<String> {
+        let mut row = vec![user.username.clone()];
+        for group in &self.groups {
+            let seats = user.groups.get(&group.name).map(Vec::as_slice);
+            let cell = match seats {
+                None | Some([]) => String::new(),
+                Some([seat]) if self.discourses.len() == 1 => {
+                    if seat.owner { "owner" } else { "x" }.to_string()
+                }
+                Some(seats) => seats
+                    .iter()
+                    .map(|seat| {
+                        if seat.owner {
+                            format!("{} (owner)", seat.discourse)
+                        } else {
+                            seat.discourse.clone()
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            row.push(cell);
+        }
+        row
+    }
+}
+
+fn write_matrix_csv<W: Write>(matrix: &GroupMatrix, writer: &mut csv::Writer<W>) -> Result<()> {
+    writer.write_record(matrix.header())?;
+    for user in &matrix.users {
+        writer.write_record(matrix.row(user))?;
+    }
+    Ok(())
+}
+
+fn render_matrix_markdown(matrix: &GroupMatrix) -> String {
+    let header = matrix.header();
+    let mut out = format!("| {} |\n", header.join(" | "));
+    out.push_str(&format!("|{}\n", " --- |".repeat(header.len())));
+    for user in &matrix.users {
+        out.push_str(&format!("| {} |\n", matrix.row(user).join(" | ")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Seat, SyncEntry, WantedMember, build_matrix, parse_sync_file, plan_group_sync,
+        render_matrix_markdown, write_matrix_csv,
+    };
+    use crate::api::{GroupMember, GroupSummary};
+
+    fn member(id: u64, username: &str) -> GroupMember {
+        GroupMember {
+            id,
+            username: username.to_string(),
+            name: None,
+        }
+    }
+
+    fn wanted(username: &str, owner: bool) -> WantedMember {
+        WantedMember {
+            username: username.to_string(),
+            email: None,
+            owner,
+        }
+    }
+
+    #[test]
+    fn sync_file_skips_header_and_comments_and_merges_duplicates() {
+        let raw = "username,owner\n# staff\nalice, yes\n\nbob@example.com\nAlice,no\ncarol,0\n";
+        let entries = parse_sync_file(raw).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                SyncEntry {
+                    login: "alice".to_string(),
+                    owner: true
+                },
+                SyncEntry {
+                    login: "bob@example.com".to_string(),
+                    owner: false
+                },
+                SyncEntry {
+                    login: "carol".to_string(),
+                    owner: false
+                },
+            ]
+        );
+        assert!(parse_sync_file("alice,maybe\n").is_err());
+    }
+
+    #[test]
+    fn plan_adds_removes_and_changes_owners() {
+        let members = vec![member(1, "alice"), member(2, "Bob"), member(3, "dave")];
+        let owners = vec![member(3, "dave")];
+        let file = vec![
+            wanted("alice", true),
+            wanted("bob", false),
+            wanted("carol", false),
+            WantedMember {
+                username: "erin".to_string(),
+                email: Some("erin@example.com".to_string()),
+                owner: false,
+            },
+        ];
+        let plan = plan_group_sync(&file, &members, &owners, true);
+        assert_eq!(plan.add, vec!["carol"]);
+        assert_eq!(plan.add_by_email, vec!["erin@example.com"]);
+        assert_eq!(plan.promote, vec!["alice"]);
+        assert!(plan.demote.is_empty());
+        assert_eq!(plan.remove, vec![member(3, "dave")]);
+
+        let plan = plan_group_sync(&file, &members, &owners, false);
+        assert!(plan.remove.is_empty());
+        assert_eq!(plan.kept, 1);
+    }
+
+    #[test]
+    fn plan_demotes_owners_the_file_lists_as_members() {
+        let members = vec![member(3, "dave")];
+        let owners = vec![member(3, "dave")];
+        let plan = plan_group_sync(&[wanted("dave", false)], &members, &owners, true);
+        assert_eq!(plan.demote, vec![member(3, "dave")]);
+        assert!(plan.add.is_empty() && plan.remove.is_empty());
+    }
+
+    fn group(discourse: &str, id: u64, name: &str, automatic: bool) -> (String, GroupSummary) {
+        (
+            discourse.to_string(),
+            GroupSummary {
+                id,
+                name: name.to_string(),
+                full_name: None,
+                automatic,
+            },
+        )
+    }
+
+    fn seat(discourse: &str, group: &str, username: &str, owner: bool) -> Seat {
+        Seat {
+            discourse: discourse.to_string(),
+            group: group.to_string(),
+            username: username.to_string(),
+            owner,
+        }
+    }
+
+    #[test]
+    fn matrix_merges_groups_and_users_across_discourses() {
+        let groups = vec![
+            group("alpha", 1, "staff", true),
+            group("alpha", 41, "support", false),
+            group("beta", 3, "staff", true),
+            group("beta", 50, "editors", false),
+        ];
+        let seats = vec![
+            seat("alpha", "staff", "bob", false),
+            seat("alpha", "support", "Alice", true),
+            seat("beta", "staff", "bob", false),
+            seat("beta", "editors", "alice", false),
+        ];
+        let matrix = build_matrix(&groups, &seats);
+        assert_eq!(matrix.discourses, vec!["alpha", "beta"]);
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        write_matrix_csv(&matrix, &mut writer).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            "username,editors,support,staff (auto)\n\
+             Alice,beta,alpha (owner),\n\
+             bob,,,\"alpha, beta\"\n"
+        );
+
+        let json = serde_json::to_value(&matrix).unwrap();
+        assert_eq!(json["groups"][2]["automatic"], true);
+        assert_eq!(json["users"][0]["groups"]["support"][0]["owner"], true);
+    }
+
+    #[test]
+    fn single_discourse_matrix_marks_cells() {
+        let groups = vec![group("alpha", 41, "support", false)];
+        let seats = vec![
+            seat("alpha", "support", "alice", true),
+            seat("alpha", "support", "bob", false),
+        ];
+        let markdown = render_matrix_markdown(&build_matrix(&groups, &seats));
+        assert_eq!(
+            markdown,
+            "| username | support |\n| --- | --- |\n| alice | owner |\n| bob | x |\n"
+        );
+    }
+}
@@ -0,0 +1,370 @@
+use crate::api::GroupDetail;
+use crate::cli::{GroupAliasLevel, GroupFields, GroupNotificationLevel, GroupVisibility};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// What `group apply` reads.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct GroupFile {
+    pub groups: Vec<GroupSpec>,
+}
+
+impl GroupFile {
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let raw =
+            fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_yaml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))
+    }
+}
+
+/// A group's managed settings. Apart from the name, a field left out is
+/// left alone.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GroupSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility_level: Option<GroupVisibility>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mentionable_level: Option<GroupAliasLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messageable_level: Option<GroupAliasLevel>,
+    /// Users with these email domains join automatically.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub automatic_membership_email_domains: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_notification_level: Option<GroupNotificationLevel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flair_icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flair_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flair_background_color: Option<String>,
+}
+
+/// One setting `group apply` or `group edit` would change.
+#[derive(Debug, PartialEq)]
+pub(crate) struct FieldChange {
+    pub field: &'static str,
+    pub from: String,
+    pub to: String,
+}
+
+impl GroupVisibility {
+    fn level(self) -> u64 {
+        match self {
+            Self::Public => 0,
+            Self::LoggedOnUsers => 1,
+            Self::Members => 2,
+            Self::Staff => 3,
+            Self::Owners => 4,
+        }
+    }
+
+    fn from_level(level: u64) -> Option<Self> {
+        [
+            Self::Public,
+            Self::LoggedOnUsers,
+            Self::Members,
+            Self::Staff,
+            Self::Owners,
+        ]
+        .into_iter()
+        .find(|value| value.level() == level)
+    }
+}
+
+impl GroupAliasLevel {
+    fn level(self) -> u64 {
+        match self {
+            Self::Nobody => 0,
+            Self::OnlyAdmins => 1,
+            Self::ModsAndAdmins => 2,
+            Self::MembersModsAndAdmins => 3,
+            Self::OwnersModsAndAdmins => 4,
+            Self::Everyone => 99,
+        }
+    }
+
+    fn from_level(level: u64) -> Option<Self> {
+        [
+            Self::Nobody,
+            Self::OnlyAdmins,
+            Self::ModsAndAdmins,
+            Self::MembersModsAndAdmins,
+            Self::OwnersModsAndAdmins,
+            Self::Everyone,
+        ]
+        .into_iter()
+        .find(|value| value.level() == level)
+    }
+}
+
+impl GroupNotificationLevel {
+    fn level(self) -> u64 {
+        match self {
+            Self::Muted => 0,
+            Self::Regular => 1,
+            Self::Tracking => 2,
+            Self::Watching => 3,
+            Self::WatchingFirstPost => 4,
+        }
+    }
+
+    fn from_level(level: u64) -> Option<Self> {
+        [
+            Self::Muted,
+            Self::Regular,
+            Self::Tracking,
+            Self::Watching,
+            Self::WatchingFirstPost,
+        ]
+        .into_iter()
+        .find(|value| value.level() == level)
+    }
+}
+
+impl GroupSpec {
+    /// Every managed setting of a live group.
+    pub(crate) fn from_detail(detail: &GroupDetail) -> Self {
+        Self {
+            name: detail.name.clone(),
+            full_name: Some(detail.full_name.clone().unwrap_or_default()),
+            visibility_level: detail
+                .visibility_level
+                .and_then(GroupVisibility::from_level),
+            mentionable_level: detail
+                .mentionable_level
+                .and_then(GroupAliasLevel::from_level),
+            messageable_level: detail
+                .messageable_level
+                .and_then(GroupAliasLevel::from_level),
+            automatic_membership_email_domains: Some(
+                detail
+                    .automatic_membership_email_domains
+                    .as_deref()
+                    .unwrap_or("")
+                    .split('|')
+                    .map(str::trim)
+                    .filter(|domain| !domain.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ),
+            default_notification_level: detail
+                .default_notification_level
+                .and_then(GroupNotificationLevel::from_level),
+            flair_icon: Some(detail.flair_icon.clone().unwrap_or_default()),
+            flair_color: Some(detail.flair_color.clone().unwrap_or_default()),
+            flair_background_color: Some(detail.flair_background_color.clone().unwrap_or_default()),
+        }
+    }
+
+    /// The settings given to `group create` or `group edit`.
+    pub(crate) fn from_fields(name: String, fields: &GroupFields) -> Self {
+        Self {
+            name,
+            full_name: fields.full_name.clone(),
+            visibility_level: fields.visibility,
+            mentionable_level: fields.mentionable,
+            messageable_level: fields.messageable,
+            automatic_membership_email_domains: fields.email_domains.as_ref().map(|domains| {
+                domains
+                    .iter()
+                    .map(|domain| domain.trim().to_string())
+                    .filter(|domain| !domain.is_empty())
+                    .collect()
+            }),
+            default_notification_level: fields.default_notification,
+            flair_icon: fields.flair_icon.clone(),
+            flair_color: fields.flair_color.as_deref().map(hex_colour),
+            flair_background_color: fields.flair_background_color.as_deref().map(hex_colour),
+        }
+    }
+
+    /// The settings this spec sets to something other than `live`.
+    pub(crate) fn changes(&self, live: &GroupSpec) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        // Discourse group names are case-insensitive, and `group apply`
+        // matches them that way.
+        if !self.name.eq_ignore_ascii_case(&live.name) {
+            changes.push(FieldChange {
+                field: "name",
+                from: live.name.clone(),
+                to: self.name.clone(),
+            });
+        }
+        let mut check = |field: &'static str, wanted: Option<String>, live: Option<String>| {
+            if let Some(wanted) = wanted
+                && Some(&wanted) != live.as_ref()
+            {
+                changes.push(FieldChange {
+                    field,
+                    from: live.unwrap_or_else(|| "-".to_string()),
+                    to: wanted,
+                });
+            }
+        };
+        check(
+            "full_name",
+            describe(&self.full_name),
+            describe(&live.full_name),
+        );
+        check(
+            "visibility_level",
+            describe(&self.visibility_level),
+            describe(&live.visibility_level),
+        );
+        check(
+            "mentionable_level",
+            describe(&self.mentionable_level),
+            describe(&live.mentionable_level),
+        );
+        check(
+            "messageable_level",
+            describe(&self.messageable_level),
+            describe(&live.messageable_level),
+        );
+        check(
+            "automatic_membership_email_domains",
+            describe(&self.automatic_membership_email_domains),
+            describe(&live.automatic_membership_email_domains),
+        );
+        check(
+            "default_notification_level",
+            describe(&self.default_notification_level),
+            describe(&live.default_notification_level),
+        );
+        check(
+            "flair_icon",
+            describe(&self.flair_icon),
+            describe(&live.flair_icon),
+        );
+        check(
+            "flair_color",
+            describe(&self.flair_color.as_deref().map(hex_colour)),
+            describe(&live.flair_color.as_deref().map(hex_colour)),
+        );
+        check(
+            "flair_background_color",
+            describe(&self.flair_background_color.as_deref().map(hex_colour)),
+            describe(&live.flair_background_color.as_deref().map(hex_colour)),
+        );
+        changes
+    }
+
+    /// `base` with every setting this spec sets applied.
+    pub(crate) fn apply_to(&self, base: &GroupDetail) -> GroupDetail {
+        let mut detail = base.clone();
+        if !self.name.eq_ignore_ascii_case(&base.name) {
+            detail.name = self.name.clone();
+        }
+        if self.full_name.is_some() {
+            detail.full_name = self.full_name.clone();
+        }
+        if let Some(level) = self.visibility_level {
+            detail.visibility_level = Some(level.level());
+        }
+        if let Some(level) = self.mentionable_level {
+            detail.mentionable_level = Some(level.level());
+        }
+        if let Some(level) = self.messageable_level {
+            detail.messageable_level = Some(level.level());
+        }
+        if let Some(domains) = &self.automatic_membership_email_domains {
+            detail.automatic_membership_email_domains = Some(domains.join("|"));
+        }
+        if let Some(level) = self.default_notification_level {
+            detail.default_notification_level = Some(level.level());
+        }
+        if self.flair_icon.is_some() {
+            detail.flair_icon = self.flair_icon.clone();
+        }
+        if let Some(colour) = &self.flair_color {
+            detail.flair_color = Some(hex_colour(colour));
+        }
+        if let Some(colour) = &self.flair_background_color {
+            detail.flair_background_color = Some(hex_colour(colour));
+        }
+        detail
+    }
+}
+
+/// A colour as Discourse stores it: hex digits, no `#`, uppercase.
+fn hex_colour(raw: &str) -> String {
+    raw.trim().trim_start_matches('#').to_ascii_uppercase()
+}
+
+/// A setting as shown in a plan, or `None` when it is not set.
+fn describe<T: Serialize>(value: &Option<T>) -> Option<String> {
+    value
+        .as_ref()
+        .and_then(|value| serde_json::to_string(value).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn live() -> GroupDetail {
+        GroupDetail {
+            id: 41,
+            name: "support".to_string(),
+            full_name: Some("Support team".to_string()),
+            visibility_level: Some(2),
+            mentionable_level: Some(99),
+            messageable_level: Some(3),
+            automatic_membership_email_domains: Some("example.com|example.org".to_string()),
+            default_notification_level: Some(3),
+            flair_color: Some("0088cc".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn live_group_roundtrips_through_yaml_unchanged() {
+        let spec = GroupSpec::from_detail(&live());
+        assert_eq!(spec.visibility_level, Some(GroupVisibility::Members));
+        assert_eq!(spec.mentionable_level, Some(GroupAliasLevel::Everyone));
+        let yaml = serde_yaml::to_string(&spec).unwrap();
+        assert!(yaml.contains("messageable_level: members-mods-and-admins"));
+        let parsed: GroupSpec = serde_yaml::from_str(&yaml).unwrap();
+        assert!(parsed.changes(&spec).is_empty());
+    }
+
+    #[test]
+    fn name_differing_only_in_case_is_not_a_rename() {
+        let wanted: GroupSpec = serde_yaml::from_str("name: Support\n").unwrap();
+        assert!(wanted.changes(&GroupSpec::from_detail(&live())).is_empty());
+        assert_eq!(wanted.apply_to(&live()).name, "support");
+        assert_eq!(wanted.apply_to(&GroupDetail::default()).name, "Support");
+    }
+
+    #[test]
+    fn only_listed_settings_are_compared_and_applied() {
+        let wanted: GroupSpec = serde_yaml::from_str(
+            "name: support\nvisibility_level: staff\nflair_color: '#0088CC'\n\
+             automatic_membership_email_domains: [example.com]\n",
+        )
+        .unwrap();
+        let current = GroupSpec::from_detail(&live());
+        let changes = wanted.changes(&current);
+        let fields: Vec<&str> = changes.iter().map(|change| change.field).collect();
+        assert_eq!(
+            fields,
+            vec!["visibility_level", "automatic_membership_email_domains"]
+        );
+        assert_eq!(changes[0].from, "\"members\"");
+        assert_eq!(changes[0].to, "\"staff\"");
+
+        let applied = wanted.apply_to(&live());
+        assert_eq!(applied.visibility_level, Some(3));
+        assert_eq!(applied.mentionable_level, Some(99));
+        assert_eq!(
+            applied.automatic_membership_email_domains.as_deref(),
+            Some("example.com")
+        );
+    }
+}
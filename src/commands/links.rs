@@ -0,0 +1,522 @@
+use crate::api::{DiscourseClient, ResourceAccess};
+use crate::cli::ReportFormat;
+use crate::commands::category::resolve_category_id;
+use crate::commands::common::{ensure_api_credentials, select_discourse};
+use crate::config::Config;
+use anyhow::{Context, Result, anyhow};
+use indicatif::{ProgressBar, ProgressStyle};
+use regex::Regex;
+use reqwest::blocking::Client;
+use reqwest::{StatusCode, Url};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Markdown links and images, autolinks, HTML `href`/`src` attributes and
+/// bare URLs, in that order.
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"\]\(\s*<?([^)\s>]+)|<(https?://[^>\s]+)>|(?:href|src)\s*=\s*["']([^"']+)["']|(https?://[^\s<>"'()\[\]`]+)"#,
+    )
+    .expect("link pattern")
+});
+
+static INLINE_CODE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`\n]*`").expect("inline code pattern"));
+
+/// How hard `links check` may hit external hosts.
+pub struct ProbeLimits {
+    pub concurrency: usize,
+    /// Requests per second to any one host.
+    pub per_host_rate: f64,
+    pub timeout: Duration,
+}
+
+/// What a link points at, and so how it is checked.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum LinkTarget {
+    Topic(u64),
+    Post(u64),
+    External(String),
+}
+
+#[derive(Debug, Clone)]
+struct LinkStatus {
+    status: String,
+    ok: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct LinkReportRow {
+    post_url: String,
+    link: String,
+    status: String,
+    ok: bool,
+}
+
+struct FoundLink {
+    post_url: String,
+    link: String,
+    target: LinkTarget,
+}
+
+pub fn links_check(
+    config: &Config,
+    discourse_name: &str,
+    category: Option<&str>,
+    all: bool,
+    limits: &ProbeLimits,
+    format: ReportFormat,
+) -> Result<()> {
+    if limits.concurrency == 0 {
+        return Err(anyhow!("--concurrency must be at least 1"));
+    }
+    let interval = Duration::try_from_secs_f64(1.0 / limits.per_host_rate)
+        .map_err(|_| anyhow!("--per-host-rate must be a positive number"))?;
+
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let base = Url::parse(client.baseurl())
+        .with_context(|| format!("parsing base URL {}", client.baseurl()))?;
+
+    let topics = match category {
+        Some(category) => {
+            let category_id = resolve_category_id(&client, category)?;
+            client.fetch_category(category_id, true)?.topic_list.topics
+        }
+        None => client.fetch_latest_topics()?,
+    };
+
+    let bar = progress_bar(topics.len());
+    let mut found = Vec::new();
+    for topic in &topics {
+        bar.set_message(topic.title.clone());
+        let response = client.fetch_topic(topic.id, true)?;
+        for post in client.fetch_topic_posts(&response)? {
+            let Some(raw) = post.raw.as_deref() else {
+                continue;
+            };
+            let post_url = format!(
+                "{}/t/{}/{}/{}",
+                client.baseurl(),
+                topic.slug,
+                topic.id,
+                post.post_number.unwrap_or(1)
+            );
+            for link in extract_links(raw) {
+                if let Some(target) = classify_link(&base, &link) {
+                    found.push(FoundLink {
+                        post_url: post_url.clone(),
+                        link,
+                        target,
+                    });
+                }
+            }
+        }
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    let targets: BTreeSet<&LinkTarget> = found.iter().map(|link| &link.target).collect();
+    let mut statuses = HashMap::new();
+    let mut urls = Vec::new();
+    for target in targets {
+        let access = match target {
+            LinkTarget::Topic(id) => client.topic_access(*id),
+            LinkTarget::Post(id) => client.post_access(*id),
+            LinkTarget::External(url) => {
+                urls.push(url.clone());
+                continue;
+            }
+        };
+        statuses.insert(target.clone(), internal_link_status(access));
+    }
+    for (url, status) in probe_urls(&urls, limits, interval)? {
+        statuses.insert(LinkTarget::External(url), status);
+    }
+
+    let total = found.len();
+    let rows: Vec<LinkReportRow> = found
+        .into_iter()
+        .filter_map(|link| {
+            let status = statuses.get(&link.target)?;
+            Some(LinkReportRow {
+                post_url: link.post_url,
+                link: link.link,
+                status: status.status.clone(),
+                ok: status.ok,
+            })
+        })
+        .collect();
+    let broken = rows.iter().filter(|row| !row.ok).count();
+    let rows: Vec<LinkReportRow> = rows.into_iter().filter(|row| all || !row.ok).collect();
+    print_link_report(&rows, format)?;
+
+    if broken > 0 {
+        return Err(anyhow!("{} of {} link(s) broken", broken, total));
+    }
+    if matches!(format, ReportFormat::Text) {
+        println!(
+            "Checked {} link(s) in {} topic(s); none broken.",
+            total,
+            topics.len()
+        );
+    }
+    Ok(())
+}
+
+fn print_link_report(rows: &[LinkReportRow], format: ReportFormat) -> Result<()> {
+    match format {
+        ReportFormat::Text => {
+            let width = rows.iter().map(|row| row.status.len()).max().unwrap_or(0);
+            for row in rows {
+                println!(
+                    "{:<width$}  {}  ({})",
+                    row.status,
+                    row.link,
+                    row.post_url,
+                    width = width
+                );
+            }
+        }
+        ReportFormat::Json => {
+            let raw = serde_json::to_string_pretty(rows)?;
+            println!("{}", raw);
+        }
+        ReportFormat::Yaml => {
+            let raw = serde_yaml::to_string(rows)?;
+            println!("{}", raw);
+        }
+        ReportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            writer.write_record(["post_url", "link", "status", "ok"])?;
+            for row in rows {
+                let ok = row.ok.to_string();
+                writer.write_record([
+                    row.post_url.as_str(),
+                    row.link.as_str(),
+                    row.status.as_str(),
+                    ok.as_str(),
+                ])?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+fn progress_bar(len: usize) -> ProgressBar {
+    let bar = ProgressBar::new(len as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    bar
+}
+
+/// Probe `urls` from `limits.concurrency` worker threads, sending each
+/// host at most one request per `interval`.
+fn probe_urls(
+    urls: &[String],
+    limits: &ProbeLimits,
+    interval: Duration,
+) -> Result<HashMap<String, LinkStatus>> {
+    let client = Client::builder()
+        .timeout(limits.timeout)
+        .user_agent(concat!("dsc/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("building http client")?;
+    let limiter = HostLimiter::new(interval);
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(HashMap::new());
+    let bar = progress_bar(urls.len());
+    thread::scope(|scope| {
+        for _ in 0..limits.concurrency.min(urls.len()) {
+            scope.spawn(|| {
+                while let Some(url) = urls.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    bar.set_message(url.clone());
+                    let status = probe_url(&client, &limiter, url);
+                    results
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(url.clone(), status);
+                    bar.inc(1);
+                }
+            });
+        }
+    });
+    bar.finish_and_clear();
+    Ok(results.into_inner().unwrap_or_else(PoisonError::into_inner))
+}
+
+fn probe_url(client: &Client, limiter: &HostLimiter, url: &str) -> LinkStatus {
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_string))
+        .unwrap_or_default();
+    limiter.wait(&host);
+    let response = match client.head(url).send() {
+        // Some servers refuse HEAD; ask again with GET.
+        Ok(response)
+            if matches!(
+                response.status(),
+                StatusCode::METHOD_NOT_ALLOWED
+                    | StatusCode::FORBIDDEN
+                    | StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            limiter.wait(&host);
+            client.get(url).send()
+        }
+        other => other,
+    };
+    match response {
+        Ok(response) => LinkStatus {
+            status: response.status().as_u16().to_string(),
+            ok: response.status().is_success(),
+        },
+        Err(err) if err.is_timeout() => LinkStatus {
+            status: "timeout".to_string(),
+            ok: false,
+        },
+        Err(err) if err.is_connect() => LinkStatus {
+            status: "connection failed".to_string(),
+            ok: false,
+        },
+        Err(err) => LinkStatus {
+            status: format!("error: {}", err),
+            ok: false,
+        },
+    }
+}
+
+/// Spaces out requests to each host.
+struct HostLimiter {
+    interval: Duration,
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl HostLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Block until `host` may be sent another request.
+    fn wait(&self, host: &str) {
+        let slot = {
+            let mut next_slot = self
+                .next_slot
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let now = Instant::now();
+            let slot = next_slot
+                .get(host)
+                .copied()
+                .filter(|at| *at > now)
+                .unwrap_or(now);
+            next_slot.insert(host.to_string(), slot + self.interval);
+            slot
+        };
+        thread::sleep(slot.saturating_duration_since(Instant::now()));
+    }
+}
+
+/// Every distinct link in a post's raw Markdown, in order, ignoring code.
+fn extract_links(raw: &str) -> Vec<String> {
+    let text = strip_code(raw);
+    let mut links: Vec<String> = Vec::new();
+    for caps in LINK_RE.captures_iter(&text) {
+        let link = match caps.get(4) {
+            Some(bare) => bare
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_']),
+            None => caps
+                .iter()
+                .skip(1)
+                .flatten()
+                .next()
+                .map_or("", |m| m.as_str()),
+        };
+        if !link.is_empty() && !links.iter().any(|seen| seen == link) {
+            links.push(link.to_string());
+        }
+    }
+    links
+}
+
+/// `raw` without fenced code blocks or inline code spans.
+fn strip_code(raw: &str) -> String {
+    let mut out = String::new();
+    let mut fence: Option<&str> = None;
+    for line in raw.lines() {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker));
+        match (fence, marker) {
+            (None, Some(marker)) => {
+                fence = Some(marker);
+                continue;
+            }
+            (Some(open), Some(marker)) if open == marker => {
+                fence = None;
+                continue;
+            }
+            (Some(_), _) => continue,
+            (None, None) => {}
+        }
+        out.push_str(&INLINE_CODE_RE.replace_all(line, ""));
+        out.push('\n');
+    }
+    out
+}
+
+/// Decide how to check `link`. Returns `None` for links that aren't
+/// checked: `upload://` short links, `mailto:`, anchors and relative paths.
+fn classify_link(base: &Url, link: &str) -> Option<LinkTarget> {
+    let mut url = if link.starts_with("http://") || link.starts_with("https://") {
+        Url::parse(link).ok()?
+    } else if link.starts_with("//") {
+        Url::parse(&format!("https:{}", link)).ok()?
+    } else if link.starts_with('/') {
+        base.join(link).ok()?
+    } else {
+        return None;
+    };
+    url.set_fragment(None);
+    if url.host_str() == base.host_str() {
+        let prefix = base.path().trim_end_matches('/');
+        if let Some(target) = url.path().strip_prefix(prefix).and_then(internal_target) {
+            return Some(target);
+        }
+    }
+    Some(LinkTarget::External(url.to_string()))
+}
+
+/// The topic or post a `/t/...` or `/p/...` path refers to.
+fn internal_target(path: &str) -> Option<LinkTarget> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    match segments.next()? {
+        "t" => {
+            let first = segments.next()?;
+            let id = first
+                .parse()
+                .ok()
+                .or_else(|| segments.next()?.parse().ok())?;
+            Some(LinkTarget::Topic(id))
+        }
+        "p" => segments.next()?.parse().ok().map(LinkTarget::Post),
+        _ => None,
+    }
+}
+
+/// Report status for a topic or post link. Only a missing target is
+/// broken; a 403 (private category, PM) works for people who can see it.
+fn internal_link_status(access: Result<ResourceAccess>) -> LinkStatus {
+    match access {
+        Ok(ResourceAccess::Visible) => LinkStatus {
+            status: "ok".to_string(),
+            ok: true,
+        },
+        Ok(ResourceAccess::Forbidden) => LinkStatus {
+            status: "no access".to_string(),
+            ok: true,
+        },
+        Ok(ResourceAccess::NotFound) => LinkStatus {
+            status: "not found".to_string(),
+            ok: false,
+        },
+        Err(err) => LinkStatus {
+            status: format!("error: {}", err),
+            ok: false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_missing_internal_targets_are_broken() {
+        let visible = internal_link_status(Ok(ResourceAccess::Visible));
+        assert!(visible.ok);
+        let forbidden = internal_link_status(Ok(ResourceAccess::Forbidden));
+        assert_eq!(forbidden.status, "no access");
+        assert!(forbidden.ok);
+        let missing = internal_link_status(Ok(ResourceAccess::NotFound));
+        assert_eq!(missing.status, "not found");
+        assert!(!missing.ok);
+    }
+
+    #[test]
+    fn extracts_markdown_html_and_bare_links_outside_code() {
+        let raw = "See [the guide](https://example.com/guide \"Guide\") and ![logo](/uploads/logo.png).\n\
+                   <a href=\"https://example.org/a\">a</a> or https://example.net/b.\n\
+                   Also <https://example.com/auto> and https://example.com/guide again.\n\
+                   `https://example.com/inline`\n\
+                   ```\n\
+                   https://example.com/fenced\n\
+                   ```\n";
+        assert_eq!(
+            extract_links(raw),
+            vec![
+                "https://example.com/guide",
+                "/uploads/logo.png",
+                "https://example.org/a",
+                "https://example.net/b",
+                "https://example.com/auto",
+            ]
+        );
+    }
+
+    #[test]
+    fn classifies_internal_and_external_links() {
+        let base = Url::parse("https://forum.example.com").unwrap();
+        assert_eq!(
+            classify_link(&base, "/t/welcome/12/3"),
+            Some(LinkTarget::Topic(12))
+        );
+        assert_eq!(
+            classify_link(&base, "https://forum.example.com/t/12"),
+            Some(LinkTarget::Topic(12))
+        );
+        assert_eq!(classify_link(&base, "/p/345"), Some(LinkTarget::Post(345)));
+        assert_eq!(
+            classify_link(&base, "https://example.org/page#section"),
+            Some(LinkTarget::External("https://example.org/page".to_string()))
+        );
+        assert_eq!(
+            classify_link(&base, "/u/alice"),
+            Some(LinkTarget::External(
+                "https://forum.example.com/u/alice".to_string()
+            ))
+        );
+        assert_eq!(classify_link(&base, "upload://abc.png"), None);
+        assert_eq!(classify_link(&base, "mailto:a@example.com"), None);
+        assert_eq!(classify_link(&base, "#top"), None);
+    }
+
+    #[test]
+    fn classifies_links_under_a_subfolder_install() {
+        let base = Url::parse("https://example.com/forum").unwrap();
+        assert_eq!(
+            classify_link(&base, "https://example.com/forum/t/intro/7"),
+            Some(LinkTarget::Topic(7))
+        );
+        assert_eq!(
+            classify_link(&base, "https://example.com/t/other/7"),
+            Some(LinkTarget::External(
+                "https://example.com/t/other/7".to_string()
+            ))
+        );
+    }
+}
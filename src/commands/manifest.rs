@@ -0,0 +1,120 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written by `category push` in the pushed directory.
+const MANIFEST_FILE: &str = ".dsc-manifest.toml";
+
+/// The topic a pushed file maps to, and the file's content hash when it was
+/// last pushed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ManifestEntry {
+    pub topic_id: u64,
+    pub hash: String,
+    /// The file was removed but its topic was kept, so the entry stays to
+    /// track it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub orphaned: bool,
+}
+
+/// Pushed files per Discourse: `discourse name -> relative path -> entry`.
+#[derive(Debug, Default)]
+pub(crate) struct Manifest {
+    path: PathBuf,
+    discourses: BTreeMap<String, BTreeMap<String, ManifestEntry>>,
+}
+
+impl Manifest {
+    /// Load the manifest in `dir`, or start an empty one.
+    pub(crate) fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let discourses = if path.exists() {
+            let raw =
+                fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            toml::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self { path, discourses })
+    }
+
+    /// Entries recorded for `discourse_name`.
+    pub(crate) fn files(&self, discourse_name: &str) -> BTreeMap<String, ManifestEntry> {
+        self.discourses
+            .get(discourse_name)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn insert(&mut self, discourse_name: &str, key: String, entry: ManifestEntry) {
+        self.discourses
+            .entry(discourse_name.to_string())
+            .or_default()
+            .insert(key, entry);
+    }
+
+    pub(crate) fn remove(&mut self, discourse_name: &str, key: &str) {
+        if let Some(files) = self.discourses.get_mut(discourse_name) {
+            files.remove(key);
+        }
+    }
+
+    pub(crate) fn save(&self) -> Result<()> {
+        let raw = toml::to_string(&self.discourses).context("serialising manifest")?;
+        fs::write(&self.path, raw).with_context(|| format!("writing {}", self.path.display()))
+    }
+}
+
+/// Manifest key for `path`: its path relative to `root`, `/`-separated.
+pub(crate) fn manifest_key(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_roundtrips_per_discourse() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut manifest = Manifest::load(dir.path()).unwrap();
+        assert!(manifest.files("myforum").is_empty());
+        let entry = ManifestEntry {
+            topic_id: 12,
+            hash: "abc".to_string(),
+            orphaned: false,
+        };
+        manifest.insert("myforum", "guides/intro.md".to_string(), entry.clone());
+        manifest.insert("myforum", "gone.md".to_string(), entry.clone());
+        manifest.remove("myforum", "gone.md");
+        let orphan = ManifestEntry {
+            orphaned: true,
+            ..entry.clone()
+        };
+        manifest.insert("myforum", "kept.md".to_string(), orphan.clone());
+        manifest.save().unwrap();
+
+        let manifest = Manifest::load(dir.path()).unwrap();
+        let files = manifest.files("myforum");
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.get("guides/intro.md"), Some(&entry));
+        assert_eq!(files.get("kept.md"), Some(&orphan));
+        assert!(manifest.files("other").is_empty());
+    }
+
+    #[test]
+    fn manifest_key_is_relative_and_slash_separated() {
+        let root = Path::new("/docs");
+        assert_eq!(
+            manifest_key(root, &root.join("guides").join("intro.md")),
+            "guides/intro.md"
+        );
+    }
+}
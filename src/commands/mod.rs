@@ -1,17 +1,22 @@
 pub mod add;
 pub mod analytics;
 pub mod api_key;
+pub mod assets;
 pub mod backup;
 pub mod category;
+pub mod category_spec;
 pub mod common;
 pub mod completions;
 pub mod config;
 pub mod emoji;
 pub mod group;
+pub mod group_spec;
 pub mod harden;
 pub mod import;
 pub mod invite;
+pub mod links;
 pub mod list;
+pub mod manifest;
 pub mod open;
 pub mod search;
 pub mod tag;
@@ -1,10 +1,18 @@
-use crate::api::DiscourseClient;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
+use crate::api::{DiscourseClient, PostInfo, PostRevision};
+use crate::cli::ListFormat;
+use crate::commands::common::{
+    confirm_action, ensure_api_credentials, print_unified_diff, select_discourse,
+};
 use crate::config::Config;
+use crate::diff::unified_hunks;
+use crate::utils::parse_since_cutoff;
 use anyhow::{Context, Result, anyhow};
+use chrono::Utc;
+use regex::Regex;
+use serde::Serialize;
 use std::fs;
 use std::io::{self, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub fn post_edit(
     config: &Config,
@@ -90,6 +98,464 @@ pub fn post_move(
     Ok(())
 }
 
+pub fn post_wiki(
+    config: &Config,
+    discourse_name: &str,
+    post_id: u64,
+    wiki: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let state = wiki_state(wiki);
+
+    if dry_run {
+        println!(
+            "[dry-run] {}: would turn wiki {} for post {}",
+            discourse.name, state, post_id
+        );
+        return Ok(());
+    }
+
+    client.set_post_wiki(post_id, wiki)?;
+    println!("Post {}: wiki {}", post_id, state);
+    Ok(())
+}
+
+fn wiki_state(wiki: bool) -> &'static str {
+    if wiki { "on" } else { "off" }
+}
+
+pub fn post_history(
+    config: &Config,
+    discourse_name: &str,
+    post_id: u64,
+    format: ListFormat,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    let mut revisions = Vec::new();
+    if let Some(latest) = client.fetch_latest_post_revision(post_id)? {
+        let last = latest.last_revision.unwrap_or(latest.current_revision);
+        for number in 2..last {
+            revisions.push(client.fetch_post_revision(post_id, number)?);
+        }
+        revisions.push(latest);
+    }
+
+    match format {
+        ListFormat::Text => {
+            if revisions.is_empty() {
+                println!("Post {} has no revisions.", post_id);
+                return Ok(());
+            }
+            let editor_width = revisions
+                .iter()
+                .map(|r| revision_editor(r).len())
+                .max()
+                .unwrap_or(0);
+            for revision in &revisions {
+                println!(
+                    "{:>4}  {:<24}  {:<width$}  {}",
+                    revision.current_revision,
+                    revision.created_at.as_deref().unwrap_or("-"),
+                    revision_editor(revision),
+                    revision
+                        .edit_reason
+                        .as_deref()
+                        .filter(|reason| !reason.trim().is_empty())
+                        .unwrap_or("-"),
+                    width = editor_width
+                );
+            }
+        }
+        ListFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&revisions)?);
+        }
+        ListFormat::Yaml => {
+            println!("{}", serde_yaml::to_string(&revisions)?);
+        }
+    }
+    Ok(())
+}
+
+fn revision_editor(revision: &PostRevision) -> &str {
+    revision.username.as_deref().unwrap_or("unknown")
+}
+
+pub fn post_show_revision(
+    config: &Config,
+    discourse_name: &str,
+    post_id: u64,
+    revision: u64,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    let (_, raw) = raw_at_revision(&client, post_id, revision)?;
+    print!("{}", raw);
+    if !raw.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+/// Put a post's body back to what `post show-revision` prints for
+/// `revision`. Discourse records the change as a new revision.
+pub fn post_revert(
+    config: &Config,
+    discourse_name: &str,
+    post_id: u64,
+    revision: u64,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    let (info, restored) = raw_at_revision(&client, post_id, revision)?;
+    let current = info.raw.unwrap_or_default();
+    if current.trim_end() == restored.trim_end() {
+        println!("Post {} already matches revision {}", post_id, revision);
+        return Ok(());
+    }
+    let hunks = unified_hunks(&current, &restored, 3);
+    print_unified_diff(
+        &format!("post {} (current)", post_id),
+        &format!("post {} (revision {})", post_id, revision),
+        &hunks,
+    );
+    if dry_run {
+        println!(
+            "[dry-run] {}: would restore post {} to revision {}",
+            discourse.name, post_id, revision
+        );
+        return Ok(());
+    }
+
+    let reason = format!("Restore revision {}", revision);
+    client.update_post_with_reason(post_id, &restored, Some(&reason))?;
+    println!("Post {}: restored revision {}", post_id, revision);
+    Ok(())
+}
+
+/// A post and its raw Markdown as it was after `revision`. This is the one
+/// meaning of a revision number that `post history`, `post show-revision`
+/// and `post revert` share.
+fn raw_at_revision(
+    client: &DiscourseClient,
+    post_id: u64,
+    revision: u64,
+) -> Result<(PostInfo, String)> {
+    let info = client.fetch_post(post_id)?;
+    let version = match info.version {
+        Some(version) => version,
+        None => client
+            .fetch_latest_post_revision(post_id)?
+            .map_or(1, |latest| latest.current_revision),
+    };
+    let raw = match revision_source(post_id, revision, version)? {
+        RevisionSource::Current => info
+            .raw
+            .clone()
+            .ok_or_else(|| anyhow!("post {} has no raw content", post_id))?,
+        RevisionSource::Original => match client.fetch_post_original_raw(post_id, version)? {
+            Some(raw) => raw,
+            None => info
+                .raw
+                .clone()
+                .ok_or_else(|| anyhow!("post {} has no raw content", post_id))?,
+        },
+        RevisionSource::Edit(revision) => {
+            let post_number = info
+                .post_number
+                .ok_or_else(|| anyhow!("post {} has no post number", post_id))?;
+            client.fetch_post_raw_at_revision(info.topic_id, post_number, revision)?
+        }
+    };
+    Ok((info, raw))
+}
+
+/// Where the body for a revision comes from.
+#[derive(Debug, PartialEq)]
+enum RevisionSource {
+    /// The latest revision: the post as it is now.
+    Current,
+    /// Revision 1 of an edited post, recorded by the edit that replaced it.
+    Original,
+    /// An earlier edit, served by `/raw`.
+    Edit(u64),
+}
+
+fn revision_source(post_id: u64, revision: u64, version: u64) -> Result<RevisionSource> {
+    if revision == 0 {
+        return Err(anyhow!(
+            "revisions are numbered from 1 (see `dsc post history`)"
+        ));
+    }
+    if revision > version {
+        return Err(anyhow!(
+            "post {} has no revision {}; its latest is {}",
+            post_id,
+            revision,
+            version
+        ));
+    }
+    Ok(match revision {
+        _ if revision == version => RevisionSource::Current,
+        1 => RevisionSource::Original,
+        _ => RevisionSource::Edit(revision),
+    })
+}
+
+/// Which posts `post replace` searches.
+#[derive(Debug, Default)]
+pub struct ReplaceScope<'a> {
+    pub search: Option<&'a str>,
+    pub category: Option<&'a str>,
+    pub since: Option<&'a str>,
+    pub user: Option<&'a str>,
+}
+
+/// Written by `post replace` so a run can be reviewed or reverted.
+#[derive(Debug, Serialize)]
+struct ReplaceLog {
+    discourse: String,
+    pattern: String,
+    replacement: String,
+    query: String,
+    edit_reason: String,
+    started_at: String,
+    changes: Vec<ReplaceLogEntry>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReplaceLogEntry {
+    post_id: u64,
+    topic_id: u64,
+    post_number: Option<u64>,
+    /// The revision this edit created.
+    revision: Option<u64>,
+    /// The revision to `dsc post revert` to undo this edit; 1, the original
+    /// text, when it was the post's first edit.
+    revert_to: Option<u64>,
+    before: String,
+    after: String,
+}
+
+/// The revision before `revision`, which holds the body it replaced.
+fn revision_before(revision: Option<u64>) -> Option<u64> {
+    revision
+        .filter(|revision| *revision > 1)
+        .map(|revision| revision - 1)
+}
+
+struct PendingReplace {
+    post_id: u64,
+    topic_id: u64,
+    post_number: Option<u64>,
+    before: String,
+    after: String,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn post_replace(
+    config: &Config,
+    discourse_name: &str,
+    pattern: &str,
+    replacement: &str,
+    scope: &ReplaceScope,
+    reason: Option<&str>,
+    log_path: Option<&Path>,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    let regex = Regex::new(pattern).with_context(|| format!("invalid pattern {:?}", pattern))?;
+    let query = replace_search_query(pattern, scope)?;
+    let candidates = client.search_all_posts(&query)?;
+    println!("Search {:?}: {} candidate post(s)", query, candidates.len());
+
+    let mut pending = Vec::new();
+    for candidate in &candidates {
+        let post = client.fetch_post(candidate.id)?;
+        let Some(before) = post.raw else {
+            continue;
+        };
+        let after = regex.replace_all(&before, replacement).into_owned();
+        if after == before {
+            continue;
+        }
+        let hunks = unified_hunks(&before, &after, 3);
+        print_unified_diff(
+            &format!("post {} (topic {})", post.id, post.topic_id),
+            "replaced",
+            &hunks,
+        );
+        pending.push(PendingReplace {
+            post_id: post.id,
+            topic_id: post.topic_id,
+            post_number: post.post_number,
+            before,
+            after,
+        });
+    }
+
+    if pending.is_empty() {
+        println!("No posts match {:?}", pattern);
+        return Ok(());
+    }
+    if dry_run {
+        println!(
+            "[dry-run] {}: would update {} post(s)",
+            discourse.name,
+            pending.len()
+        );
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&format!("update {} post(s)", pending.len()))? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let edit_reason = reason
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("Replace /{}/", pattern));
+    let log_path = log_path.map(Path::to_path_buf).unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "dsc-replace-{}-{}.json",
+            discourse.name,
+            Utc::now().format("%Y%m%dT%H%M%SZ")
+        ))
+    });
+    let mut log = ReplaceLog {
+        discourse: discourse.name.clone(),
+        pattern: pattern.to_string(),
+        replacement: replacement.to_string(),
+        query,
+        edit_reason: edit_reason.clone(),
+        started_at: Utc::now().to_rfc3339(),
+        changes: Vec::new(),
+    };
+    for change in pending {
+        client.update_post_with_reason(change.post_id, &change.after, Some(&edit_reason))?;
+        let revision = client.fetch_post(change.post_id)?.version;
+        println!("Post {} updated", change.post_id);
+        log.changes.push(ReplaceLogEntry {
+            post_id: change.post_id,
+            topic_id: change.topic_id,
+            post_number: change.post_number,
+            revision,
+            revert_to: revision_before(revision),
+            before: change.before,
+            after: change.after,
+        });
+        // Rewrite after every edit so an interrupted run still has a log.
+        write_replace_log(&log_path, &log)?;
+    }
+    println!(
+        "Updated {} post(s); log written to {}",
+        log.changes.len(),
+        log_path.display()
+    );
+    Ok(())
+}
+
+fn write_replace_log(path: &Path, log: &ReplaceLog) -> Result<()> {
+    let raw = serde_json::to_string_pretty(log).context("serialising replace log")?;
+    fs::write(path, raw).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Discourse search query for the posts `post replace` should check.
+fn replace_search_query(pattern: &str, scope: &ReplaceScope) -> Result<String> {
+    let terms = match scope.search {
+        Some(search) => search.to_string(),
+        None => {
+            let hint = literal_hint(pattern).ok_or_else(|| {
+                anyhow!(
+                    "can't derive search terms from pattern {:?}; pass --search",
+                    pattern
+                )
+            })?;
+            format!("\"{}\"", hint.replace('"', " ").trim())
+        }
+    };
+    let mut parts = vec![terms];
+    if let Some(category) = scope.category {
+        parts.push(format!("category:{}", category));
+    }
+    if let Some(since) = scope.since {
+        let cutoff = parse_since_cutoff(since)?;
+        parts.push(format!("after:{}", cutoff.format("%Y-%m-%d")));
+    }
+    if let Some(user) = scope.user {
+        parts.push(format!("@{}", user.trim_start_matches('@')));
+    }
+    Ok(parts.join(" "))
+}
+
+/// The longest run of literal text every match of `pattern` must contain,
+/// if it is at least three characters. Alternation anywhere, and anything
+/// inside groups or classes, is treated as unknown.
+fn literal_hint(pattern: &str) -> Option<String> {
+    let mut best = String::new();
+    let mut run = String::new();
+    let mut depth = 0usize;
+    let mut chars = pattern.chars().peekable();
+    let finish = |run: &mut String, best: &mut String| {
+        if run.trim().chars().count() > best.chars().count() {
+            *best = run.trim().to_string();
+        }
+        run.clear();
+    };
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next() {
+                Some(escaped) if !escaped.is_alphanumeric() && depth == 0 => run.push(escaped),
+                _ => finish(&mut run, &mut best),
+            },
+            '|' => return None,
+            '[' => {
+                finish(&mut run, &mut best);
+                while let Some(inner) = chars.next() {
+                    match inner {
+                        '\\' => {
+                            chars.next();
+                        }
+                        ']' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' => {
+                finish(&mut run, &mut best);
+                depth += 1;
+            }
+            ')' => {
+                finish(&mut run, &mut best);
+                depth = depth.saturating_sub(1);
+            }
+            // The preceding character may be absent or repeated.
+            '?' | '*' | '{' => {
+                run.pop();
+                finish(&mut run, &mut best);
+            }
+            '.' | '^' | '$' | '+' | '}' => finish(&mut run, &mut best),
+            _ if depth == 0 => run.push(ch),
+            _ => {}
+        }
+    }
+    finish(&mut run, &mut best);
+    (best.chars().count() >= 3).then_some(best)
+}
+
 fn read_body(local_path: Option<&Path>) -> Result<String> {
     let from_stdin = match local_path {
         None => true,
@@ -109,7 +575,10 @@ fn read_body(local_path: Option<&Path>) -> Result<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::read_body;
+    use super::{
+        ReplaceScope, RevisionSource, literal_hint, read_body, replace_search_query,
+        revision_before, revision_source, wiki_state,
+    };
     use std::io::Write;
     use tempfile::NamedTempFile;
 
@@ -128,4 +597,67 @@ mod tests {
         let msg = format!("{:#}", err);
         assert!(msg.contains("/definitely/does/not/exist.md"));
     }
+
+    #[test]
+    fn revision_one_is_the_original_text() {
+        // Reverting an edited post to revision 1 restores its first draft.
+        assert_eq!(revision_source(7, 1, 3).unwrap(), RevisionSource::Original);
+        // A post never edited is its own revision 1.
+        assert_eq!(revision_source(7, 1, 1).unwrap(), RevisionSource::Current);
+        assert_eq!(revision_source(7, 2, 3).unwrap(), RevisionSource::Edit(2));
+        assert_eq!(revision_source(7, 3, 3).unwrap(), RevisionSource::Current);
+        assert!(revision_source(7, 0, 3).is_err());
+        assert!(revision_source(7, 4, 3).is_err());
+    }
+
+    #[test]
+    fn logged_first_edit_reverts_to_the_original_text() {
+        // `post replace` made the post's first edit, creating revision 2.
+        let revert_to = revision_before(Some(2)).unwrap();
+        assert_eq!(revert_to, 1);
+        assert_eq!(
+            revision_source(7, revert_to, 2).unwrap(),
+            RevisionSource::Original
+        );
+        assert_eq!(revision_before(Some(5)), Some(4));
+        assert_eq!(revision_before(None), None);
+    }
+
+    #[test]
+    fn literal_hint_picks_longest_required_run() {
+        assert_eq!(
+            literal_hint(r"http://old\.example\.com/(\w+)").as_deref(),
+            Some("http://old.example.com/")
+        );
+        assert_eq!(literal_hint("colou?r scheme").as_deref(), Some("r scheme"));
+        assert_eq!(literal_hint("foo|barbaz"), None);
+        assert_eq!(literal_hint(r"\d+-\d+"), None);
+        assert_eq!(literal_hint("(optional)? text").as_deref(), Some("text"));
+    }
+
+    #[test]
+    fn replace_query_adds_scope_filters() {
+        let scope = ReplaceScope {
+            category: Some("docs"),
+            since: Some("2026-01-01"),
+            user: Some("@alice"),
+            ..Default::default()
+        };
+        assert_eq!(
+            replace_search_query("old-name", &scope).unwrap(),
+            "\"old-name\" category:docs after:2026-01-01 @alice"
+        );
+        let scope = ReplaceScope {
+            search: Some("widget"),
+            ..Default::default()
+        };
+        assert_eq!(replace_search_query("w.dget", &scope).unwrap(), "widget");
+        assert!(replace_search_query("a|b", &ReplaceScope::default()).is_err());
+    }
+
+    #[test]
+    fn wiki_state_names_the_new_state() {
+        assert_eq!(wiki_state(true), "on");
+        assert_eq!(wiki_state(false), "off");
+    }
 }
@@ -1,28 +1,41 @@
-use crate::api::DiscourseClient;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
+use crate::api::{BulkOperation, DiscourseClient, Post, TopicResponse, TopicTimer, TopicUpdate};
+use crate::cli::{BulkAction, TimerAction};
+use crate::commands::assets::{
+    ASSETS_DIR, PullAssets, copy_uploads, map_uploaded_assets, markdown_dir, restore_local_assets,
+    upload_links, upload_local_assets,
+};
+use crate::commands::category::{category_slugs, resolve_category_id};
+use crate::commands::common::{
+    confirm_action, ensure_api_credentials, print_unified_diff, select_discourse,
+};
 use crate::config::Config;
-use crate::utils::{read_markdown, resolve_topic_path, write_markdown};
+use crate::diff::{diff_stat, has_conflict_markers, merge3, unified_hunks};
+use crate::frontmatter::{
+    FrontMatter, parse_front_matter, read_topic_file, render_front_matter, resolve_push_topic_id,
+};
+use crate::utils::{color_text, ensure_dir, resolve_topic_path, slugify, write_markdown};
 use anyhow::{Context, Result, anyhow};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::io::{self, Read, Write};
-use std::path::Path;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
 
 pub fn topic_pull(
     config: &Config,
     discourse_name: &str,
     topic_id: u64,
     local_path: Option<&Path>,
+    all_posts: bool,
+    split: bool,
+    download_uploads: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
+    let assets = PullAssets::new(&client, &discourse.name, download_uploads);
     let topic = client.fetch_topic(topic_id, true)?;
-    let raw = topic
-        .post_stream
-        .posts
-        .get(0)
-        .and_then(|p| p.raw.clone())
-        .ok_or_else(|| anyhow!("topic has no raw content"))?;
     let title = topic
         .title
         .as_deref()
@@ -36,8 +49,40 @@ pub fn topic_pull(
                 .map(|s| s.to_string())
         })
         .unwrap_or_else(|| format!("topic-{}", topic_id));
-    let target = resolve_topic_path(local_path, &title, &std::env::current_dir()?)?;
-    write_markdown(&target, &raw)?;
+    let front_matter = topic_front_matter(&discourse.name, &topic, &category_slugs(&client)?);
+    let cwd = std::env::current_dir()?;
+    if all_posts {
+        let posts = client.fetch_topic_posts(&topic)?;
+        let count = posts.len();
+        let target = if split {
+            let dir = match local_path {
+                Some(path) => path.to_path_buf(),
+                None => cwd.join(slugify(&title)),
+            };
+            write_post_files(&dir, &front_matter, &posts, &assets)?;
+            dir
+        } else {
+            let target = resolve_topic_path(local_path, &title, &cwd)?;
+            let posts = localise_posts(&assets, &markdown_dir(&target), posts)?;
+            write_markdown(&target, &render_thread(&front_matter, &posts)?)?;
+            target
+        };
+        println!("Topic pulled to: {} ({} posts)", target.display(), count);
+        return Ok(());
+    }
+
+    let raw = topic
+        .post_stream
+        .posts
+        .first()
+        .and_then(|p| p.raw.clone())
+        .ok_or_else(|| anyhow!("topic has no raw content"))?;
+    let target = resolve_topic_path(local_path, &title, &cwd)?;
+    let raw = assets.localise(&markdown_dir(&target), &raw)?;
+    write_markdown(&target, &render_front_matter(&front_matter, &raw)?)?;
+    if let Some(base) = SyncBase::from_topic(&topic) {
+        write_sync_base(&target, &base)?;
+    }
     println!("Topic pulled to: {}", target.display());
     Ok(())
 }
@@ -45,20 +90,36 @@ pub fn topic_pull(
 pub fn topic_push(
     config: &Config,
     discourse_name: &str,
-    topic_id: u64,
+    topic_id: Option<u64>,
     local_path: &Path,
     dry_run: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
+    let (front_matter, raw) = read_topic_file(local_path)?;
+    ensure_no_conflict_markers(&raw, local_path)?;
+    let topic_id =
+        resolve_push_topic_id(topic_id, front_matter.as_ref(), &discourse.name, local_path)?;
+    let raw = upload_local_assets(
+        &client,
+        &discourse.name,
+        &markdown_dir(local_path),
+        &raw,
+        dry_run,
+    )?;
     let topic = client.fetch_topic(topic_id, true)?;
     let post = topic
         .post_stream
         .posts
-        .get(0)
+        .first()
         .ok_or_else(|| anyhow!("topic has no posts"))?;
-    let raw = read_markdown(local_path)?;
+    let update = match &front_matter {
+        Some(front_matter) => {
+            topic_update_from_front_matter(front_matter, &topic, &category_slugs(&client)?)?
+        }
+        None => TopicUpdate::default(),
+    };
     if dry_run {
         println!(
             "[dry-run] {}: would replace OP of topic {} (post id {}) with {} bytes from {}",
@@ -68,27 +129,175 @@ pub fn topic_push(
             raw.len(),
             local_path.display()
         );
+        print_topic_update(&discourse.name, topic_id, &update);
         return Ok(());
     }
     client.update_post(post.id, &raw)?;
+    client.update_topic(topic_id, &update)?;
     Ok(())
 }
 
 pub fn topic_sync(
     config: &Config,
     discourse_name: &str,
-    topic_id: u64,
+    topic_id: Option<u64>,
     local_path: &Path,
     assume_yes: bool,
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
+    let (front_matter, file_raw) = read_topic_file(local_path)?;
+    let topic_id =
+        resolve_push_topic_id(topic_id, front_matter.as_ref(), &discourse.name, local_path)?;
+    // Compare and merge in terms of `upload://` links, so files pulled with
+    // --download-uploads don't look locally changed. New local files are
+    // only uploaded once a push is confirmed.
+    let base_dir = markdown_dir(local_path);
+    let local_raw = map_uploaded_assets(&discourse.name, &base_dir, &file_raw)?;
+    // Bodies written back to the file keep its local asset paths.
+    let localise = |raw: &str| restore_local_assets(&discourse.name, &base_dir, &file_raw, raw);
     let topic = client.fetch_topic(topic_id, true)?;
     let post = topic
         .post_stream
         .posts
-        .get(0)
+        .first()
+        .ok_or_else(|| anyhow!("topic has no posts"))?;
+    let remote_raw = post
+        .raw
+        .clone()
+        .ok_or_else(|| anyhow!("missing raw content"))?;
+    let remote_base = SyncBase {
+        topic_id,
+        post_id: post.id,
+        revision: post.version,
+        raw: remote_raw.clone(),
+    };
+
+    let slugs = category_slugs(&client)?;
+    if local_raw == remote_raw {
+        write_sync_base(local_path, &remote_base)?;
+        // Equal bodies can still hide a retitle, recategorise or retag made
+        // in the front matter.
+        let update = match &front_matter {
+            Some(front_matter) => topic_update_from_front_matter(front_matter, &topic, &slugs)?,
+            None => TopicUpdate::default(),
+        };
+        if update.is_empty() {
+            println!("Already in sync.");
+            return Ok(());
+        }
+        println!(
+            "Bodies match; front matter differs from topic {} ({}).",
+            topic_id,
+            changed_topic_fields(&update).join(", ")
+        );
+        if !assume_yes && !confirm_action("push the front matter to Discourse")? {
+            return Ok(());
+        }
+        client.update_topic(topic_id, &update)?;
+        return Ok(());
+    }
+    let Some(base) = read_sync_base(local_path)?.filter(|base| base.topic_id == topic_id) else {
+        println!(
+            "No sync base for {}; using the most recently modified copy.",
+            local_path.display()
+        );
+        return sync_newest_wins(
+            &client,
+            &discourse.name,
+            &topic,
+            front_matter.as_ref(),
+            &slugs,
+            local_path,
+            &file_raw,
+            assume_yes,
+        );
+    };
+
+    let local_changed = local_raw != base.raw;
+    let remote_changed = remote_raw != base.raw;
+    println!(
+        "Base revision: {}  Remote revision: {}",
+        format_revision(base.revision),
+        format_revision(post.version)
+    );
+    if !local_changed {
+        println!("Remote changed since the last sync; local is unchanged.");
+        if !assume_yes && !confirm_action("pull from Discourse")? {
+            return Ok(());
+        }
+        let front_matter = topic_front_matter(&discourse.name, &topic, &slugs);
+        write_markdown(
+            local_path,
+            &render_front_matter(&front_matter, &localise(&remote_raw)?)?,
+        )?;
+        write_sync_base(local_path, &remote_base)?;
+        return Ok(());
+    }
+    ensure_no_conflict_markers(&local_raw, local_path)?;
+
+    let front_matter =
+        front_matter.unwrap_or_else(|| topic_front_matter(&discourse.name, &topic, &slugs));
+    let raw = if remote_changed {
+        let merged = merge3(&base.raw, &local_raw, &remote_raw, "local", "remote");
+        if merged.conflicts > 0 {
+            write_markdown(
+                local_path,
+                &render_front_matter(&front_matter, &localise(&merged.text)?)?,
+            )?;
+            write_sync_base(local_path, &remote_base)?;
+            return Err(anyhow!(
+                "{} conflict(s) written to {}; resolve the markers and run sync again",
+                merged.conflicts,
+                local_path.display()
+            ));
+        }
+        println!("Both sides changed; merged cleanly.");
+        if !assume_yes && !confirm_action("write the merge and push to Discourse")? {
+            return Ok(());
+        }
+        write_markdown(
+            local_path,
+            &render_front_matter(&front_matter, &localise(&merged.text)?)?,
+        )?;
+        merged.text
+    } else {
+        println!("Local changed since the last sync; remote is unchanged.");
+        if !assume_yes && !confirm_action("push to Discourse")? {
+            return Ok(());
+        }
+        local_raw
+    };
+    let raw = upload_local_assets(&client, &discourse.name, &base_dir, &raw, false)?;
+    push_synced(
+        &client,
+        &topic,
+        Some(&front_matter),
+        &slugs,
+        local_path,
+        &raw,
+    )
+}
+
+/// Pre-merge `topic sync` behaviour, used when a file has no base snapshot
+/// yet: whichever side was modified last overwrites the other. `file_raw`
+/// is the body as it is on disk.
+#[allow(clippy::too_many_arguments)]
+fn sync_newest_wins(
+    client: &DiscourseClient,
+    discourse_name: &str,
+    topic: &TopicResponse,
+    front_matter: Option<&FrontMatter>,
+    slugs: &HashMap<u64, String>,
+    local_path: &Path,
+    file_raw: &str,
+    assume_yes: bool,
+) -> Result<()> {
+    let post = topic
+        .post_stream
+        .posts
+        .first()
         .ok_or_else(|| anyhow!("topic has no posts"))?;
     let local_meta =
         fs::metadata(local_path).with_context(|| format!("reading {}", local_path.display()))?;
@@ -110,24 +319,540 @@ pub fn topic_sync(
     println!("Remote post: {}", remote_time);
 
     let pull = remote_time > chrono::DateTime::<chrono::Utc>::from(local_mtime);
-    if !assume_yes && !confirm_sync(pull)? {
+    if !pull {
+        ensure_no_conflict_markers(file_raw, local_path)?;
+    }
+    let action = if pull {
+        "pull from Discourse"
+    } else {
+        "push to Discourse"
+    };
+    if !assume_yes && !confirm_action(action)? {
         return Ok(());
     }
 
+    let base_dir = markdown_dir(local_path);
     if pull {
         let raw = post
             .raw
             .clone()
             .ok_or_else(|| anyhow!("missing raw content"))?;
-        write_markdown(local_path, &raw)?;
+        let front_matter = topic_front_matter(discourse_name, topic, slugs);
+        let body = restore_local_assets(discourse_name, &base_dir, file_raw, &raw)?;
+        write_markdown(local_path, &render_front_matter(&front_matter, &body)?)?;
+        write_sync_base(
+            local_path,
+            &SyncBase {
+                topic_id: topic.id,
+                post_id: post.id,
+                revision: post.version,
+                raw,
+            },
+        )
+    } else {
+        let raw = upload_local_assets(client, discourse_name, &base_dir, file_raw, false)?;
+        push_synced(client, topic, front_matter, slugs, local_path, &raw)
+    }
+}
+
+/// Push `raw` (and any front matter metadata changes) to the topic's first
+/// post, then record it as the new sync base.
+fn push_synced(
+    client: &DiscourseClient,
+    topic: &TopicResponse,
+    front_matter: Option<&FrontMatter>,
+    slugs: &HashMap<u64, String>,
+    local_path: &Path,
+    raw: &str,
+) -> Result<()> {
+    let post = topic
+        .post_stream
+        .posts
+        .first()
+        .ok_or_else(|| anyhow!("topic has no posts"))?;
+    client.update_post(post.id, raw)?;
+    if let Some(front_matter) = front_matter {
+        let update = topic_update_from_front_matter(front_matter, topic, slugs)?;
+        client.update_topic(topic.id, &update)?;
+    }
+    let revision = client.fetch_post(post.id)?.version;
+    write_sync_base(
+        local_path,
+        &SyncBase {
+            topic_id: topic.id,
+            post_id: post.id,
+            revision,
+            raw: raw.to_string(),
+        },
+    )
+}
+
+pub fn topic_diff(
+    config: &Config,
+    discourse_name: &str,
+    local_path: &Path,
+    post_id: Option<u64>,
+    stat: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let files = if local_path.is_dir() {
+        if post_id.is_some() {
+            return Err(anyhow!("--post compares a single file, not a directory"));
+        }
+        let mut files = Vec::new();
+        collect_markdown_files(local_path, &mut files)?;
+        files
     } else {
-        let raw = read_markdown(local_path)?;
-        client.update_post(post.id, &raw)?;
+        vec![local_path.to_path_buf()]
+    };
+
+    let mut stats = Vec::new();
+    for path in &files {
+        let outcome = diff_file(&client, &discourse.name, path, post_id, !stat)?;
+        if !matches!(outcome, DiffOutcome::Same) {
+            stats.push((path.display().to_string(), outcome));
+        }
+    }
+    if stat {
+        print_diff_stat(&stats);
+    }
+    if stats.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "{} file(s) differ from {}",
+        stats.len(),
+        discourse.name
+    ))
+}
+
+/// How one local file compares with its post.
+enum DiffOutcome {
+    Same,
+    Changed {
+        added: usize,
+        removed: usize,
+    },
+    /// No topic on this Discourse yet; a push would create one.
+    New,
+}
+
+/// Compare `path` with its post, printing a unified diff from the forum's
+/// copy to the local one when `print` is set.
+fn diff_file(
+    client: &DiscourseClient,
+    discourse_name: &str,
+    path: &Path,
+    post_id: Option<u64>,
+    print: bool,
+) -> Result<DiffOutcome> {
+    let (front_matter, raw) = read_topic_file(path)?;
+    let front_matter = front_matter.unwrap_or_default();
+    if let Some(posts) = front_matter.posts {
+        eprintln!(
+            "Skipping {}: a {}-post thread archive has no single post to compare",
+            path.display(),
+            posts
+        );
+        return Ok(DiffOutcome::Same);
+    }
+    let topic_id = front_matter.topic_id_for(discourse_name);
+    // A post ID in front matter is only meaningful on the forum the file
+    // was pulled from.
+    let post_id = post_id.or(topic_id.and(front_matter.post_id));
+    let (post_id, remote) = match (post_id, topic_id) {
+        (Some(post_id), _) => (post_id, client.fetch_post(post_id)?.raw),
+        (None, Some(topic_id)) => {
+            let topic = client.fetch_topic(topic_id, true)?;
+            let post = topic
+                .post_stream
+                .posts
+                .first()
+                .ok_or_else(|| anyhow!("topic has no posts"))?;
+            (post.id, post.raw.clone())
+        }
+        (None, None) => {
+            if print {
+                println!("{}: no topic on {} yet", path.display(), discourse_name);
+            }
+            return Ok(DiffOutcome::New);
+        }
+    };
+    let remote = remote.ok_or_else(|| anyhow!("missing raw content"))?;
+    let local = map_uploaded_assets(discourse_name, &markdown_dir(path), &raw)?;
+    let hunks = unified_hunks(&remote, &local, 3);
+    if hunks.is_empty() {
+        return Ok(DiffOutcome::Same);
+    }
+    if print {
+        print_unified_diff(
+            &format!("{}: post {}", discourse_name, post_id),
+            &path.display().to_string(),
+            &hunks,
+        );
+    }
+    let (added, removed) = diff_stat(&hunks);
+    Ok(DiffOutcome::Changed { added, removed })
+}
+
+/// `git diff --stat` style summary of the differing files.
+fn print_diff_stat(stats: &[(String, DiffOutcome)]) {
+    const BAR_WIDTH: usize = 40;
+    let width = stats.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+    let largest = stats
+        .iter()
+        .map(|(_, outcome)| match outcome {
+            DiffOutcome::Changed { added, removed } => added + removed,
+            _ => 0,
+        })
+        .max()
+        .unwrap_or(0);
+    let scale = |n: usize| {
+        if largest <= BAR_WIDTH {
+            n
+        } else {
+            n.div_ceil(largest.div_ceil(BAR_WIDTH))
+        }
+    };
+    let (mut insertions, mut deletions) = (0, 0);
+    for (path, outcome) in stats {
+        match outcome {
+            DiffOutcome::Changed { added, removed } => {
+                insertions += added;
+                deletions += removed;
+                println!(
+                    " {:<width$} | {:>5} {}{}",
+                    path,
+                    added + removed,
+                    color_text(&"+".repeat(scale(*added)), 32),
+                    color_text(&"-".repeat(scale(*removed)), 31),
+                    width = width
+                );
+            }
+            DiffOutcome::New => println!(" {:<width$} | new", path, width = width),
+            DiffOutcome::Same => {}
+        }
+    }
+    println!(
+        " {} file(s) changed, {} insertion(s)(+), {} deletion(s)(-)",
+        stats.len(),
+        insertions,
+        deletions
+    );
+}
+
+/// Markdown files under `dir`, sorted, skipping hidden directories and
+/// downloaded assets.
+fn collect_markdown_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let mut paths = Vec::new();
+    let entries = fs::read_dir(dir).with_context(|| format!("reading {}", dir.display()))?;
+    for entry in entries {
+        paths.push(entry?.path());
     }
+    paths.sort();
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != ASSETS_DIR {
+                collect_markdown_files(&path, out)?;
+            }
+        } else if path.extension().and_then(|s| s.to_str()) == Some("md") {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
 
+fn ensure_no_conflict_markers(raw: &str, path: &Path) -> Result<()> {
+    if has_conflict_markers(raw) {
+        return Err(anyhow!(
+            "{} has unresolved conflict markers; resolve them before pushing",
+            path.display()
+        ));
+    }
     Ok(())
 }
 
+fn format_revision(revision: Option<u64>) -> String {
+    revision
+        .map(|r| r.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Last-synced copy of a topic's first post, kept beside the local file as
+/// the common ancestor for `topic sync`'s three-way merge.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SyncBase {
+    pub topic_id: u64,
+    pub post_id: u64,
+    #[serde(default)]
+    pub revision: Option<u64>,
+    pub raw: String,
+}
+
+impl SyncBase {
+    /// Snapshot the first post of a fetched topic, if it has raw content.
+    pub(crate) fn from_topic(topic: &TopicResponse) -> Option<Self> {
+        let post = topic.post_stream.posts.first()?;
+        Some(Self {
+            topic_id: topic.id,
+            post_id: post.id,
+            revision: post.version,
+            raw: post.raw.clone()?,
+        })
+    }
+}
+
+/// `dir/topic.md` -> `dir/.topic.md.dsc-base`.
+pub(crate) fn sync_base_path(local_path: &Path) -> PathBuf {
+    let name = local_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    local_path.with_file_name(format!(".{}.dsc-base", name))
+}
+
+fn read_sync_base(local_path: &Path) -> Result<Option<SyncBase>> {
+    let path = sync_base_path(local_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    let base = serde_json::from_str(&raw).with_context(|| format!("parsing {}", path.display()))?;
+    Ok(Some(base))
+}
+
+pub(crate) fn write_sync_base(local_path: &Path, base: &SyncBase) -> Result<()> {
+    let path = sync_base_path(local_path);
+    let raw = serde_json::to_string_pretty(base).context("serialising sync base")?;
+    fs::write(&path, raw).with_context(|| format!("writing {}", path.display()))
+}
+
+/// Build the front matter block describing a fetched topic.
+pub(crate) fn topic_front_matter(
+    discourse_name: &str,
+    topic: &TopicResponse,
+    category_slugs: &HashMap<u64, String>,
+) -> FrontMatter {
+    let post = topic.post_stream.posts.first();
+    FrontMatter {
+        discourse: Some(discourse_name.to_string()),
+        topic_id: Some(topic.id),
+        post_id: post.map(|p| p.id),
+        title: topic.title.clone(),
+        category: topic
+            .category_id
+            .and_then(|id| category_slugs.get(&id).cloned()),
+        tags: if topic.tags.is_empty() {
+            None
+        } else {
+            Some(topic.tags.clone())
+        },
+        updated_at: post.and_then(|p| p.updated_at.clone()),
+        ..FrontMatter::default()
+    }
+}
+
+/// Render a whole thread as one Markdown document, each post under a header
+/// naming its author and timestamp. The front matter records the post count
+/// so the archive is never pushed back as the opening post.
+pub(crate) fn render_thread(front_matter: &FrontMatter, posts: &[Post]) -> Result<String> {
+    let front_matter = FrontMatter {
+        post_id: None,
+        posts: Some(posts.len()),
+        ..front_matter.clone()
+    };
+    let body = posts
+        .iter()
+        .enumerate()
+        .map(|(index, post)| {
+            format!(
+                "{}\n\n{}\n",
+                post_header(post, index),
+                post.raw.as_deref().unwrap_or_default().trim_end()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    render_front_matter(&front_matter, &body)
+}
+
+/// Apply `assets` to every post body of a thread archive in `base_dir`.
+pub(crate) fn localise_posts(
+    assets: &PullAssets,
+    base_dir: &Path,
+    posts: Vec<Post>,
+) -> Result<Vec<Post>> {
+    posts
+        .into_iter()
+        .map(|post| {
+            let raw = match &post.raw {
+                Some(raw) => Some(assets.localise(base_dir, raw)?),
+                None => None,
+            };
+            Ok(Post { raw, ..post })
+        })
+        .collect()
+}
+
+fn post_header(post: &Post, index: usize) -> String {
+    format!(
+        "## Post {} by @{} on {}",
+        post.post_number.unwrap_or(index as u64 + 1),
+        post.username.as_deref().unwrap_or("unknown"),
+        post.created_at.as_deref().unwrap_or("unknown date")
+    )
+}
+
+/// Write one file per post into `dir`, named `<post-number>-<author>.md`.
+/// Replies carry only their identity in front matter; the opening post keeps
+/// the topic's title, category and tags and gets a sync base.
+pub(crate) fn write_post_files(
+    dir: &Path,
+    front_matter: &FrontMatter,
+    posts: &[Post],
+    assets: &PullAssets,
+) -> Result<()> {
+    ensure_dir(dir)?;
+    let width = posts.len().to_string().len().max(3);
+    for (index, post) in posts.iter().enumerate() {
+        let number = post.post_number.unwrap_or(index as u64 + 1);
+        let author = post.username.as_deref().unwrap_or("unknown");
+        let post_front_matter = if number == 1 {
+            front_matter.clone()
+        } else {
+            FrontMatter {
+                discourse: front_matter.discourse.clone(),
+                topic_id: front_matter.topic_id,
+                ..FrontMatter::default()
+            }
+        };
+        let post_front_matter = FrontMatter {
+            post_id: Some(post.id),
+            post_number: Some(number),
+            author: Some(author.to_string()),
+            updated_at: post.updated_at.clone(),
+            ..post_front_matter
+        };
+        let raw = post.raw.clone().unwrap_or_default();
+        let path = dir.join(format!(
+            "{:0width$}-{}.md",
+            number,
+            slugify(author),
+            width = width
+        ));
+        let body = assets.localise(dir, &raw)?;
+        write_markdown(&path, &render_front_matter(&post_front_matter, &body)?)?;
+        if let (1, Some(topic_id)) = (number, front_matter.topic_id) {
+            let base = SyncBase {
+                topic_id,
+                post_id: post.id,
+                revision: post.version,
+                raw,
+            };
+            write_sync_base(&path, &base)?;
+        }
+    }
+    Ok(())
+}
+
+/// Work out which topic-level fields differ between a file's front matter
+/// and the live topic.
+pub(crate) fn topic_update_from_front_matter(
+    front_matter: &FrontMatter,
+    topic: &TopicResponse,
+    category_slugs: &HashMap<u64, String>,
+) -> Result<TopicUpdate> {
+    let title = front_matter
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|title| !title.is_empty() && topic.title.as_deref() != Some(*title))
+        .map(|title| title.to_string());
+    let category_id = match front_matter.category.as_deref().map(str::trim) {
+        Some(category) => {
+            let id = category
+                .parse::<u64>()
+                .ok()
+                .or_else(|| {
+                    category_slugs
+                        .iter()
+                        .find(|(_, slug)| slug.as_str() == category)
+                        .map(|(id, _)| *id)
+                })
+                .ok_or_else(|| anyhow!("category not found: {}", category))?;
+            Some(id).filter(|id| topic.category_id != Some(*id))
+        }
+        None => None,
+    };
+    let tags = front_matter.tags.as_ref().filter(|tags| {
+        let mut wanted = tags.to_vec();
+        let mut current = topic.tags.clone();
+        wanted.sort();
+        current.sort();
+        wanted != current
+    });
+    Ok(TopicUpdate {
+        title,
+        category_id,
+        tags: tags.cloned(),
+        featured_link: None,
+    })
+}
+
+/// Names of the topic fields an update would change.
+fn changed_topic_fields(update: &TopicUpdate) -> Vec<&'static str> {
+    [
+        ("title", update.title.is_some()),
+        ("category", update.category_id.is_some()),
+        ("tags", update.tags.is_some()),
+        ("featured link", update.featured_link.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(name, changed)| changed.then_some(name))
+    .collect()
+}
+
+fn print_topic_update(discourse_name: &str, topic_id: u64, update: &TopicUpdate) {
+    if let Some(title) = &update.title {
+        println!(
+            "[dry-run] {}: would retitle topic {} to \"{}\"",
+            discourse_name, topic_id, title
+        );
+    }
+    if let Some(category_id) = update.category_id {
+        println!(
+            "[dry-run] {}: would move topic {} to category {}",
+            discourse_name, topic_id, category_id
+        );
+    }
+    if let Some(tags) = &update.tags {
+        println!(
+            "[dry-run] {}: would set tags on topic {} to: [{}]",
+            discourse_name,
+            topic_id,
+            tags.join(", ")
+        );
+    }
+    if let Some(featured_link) = &update.featured_link {
+        match featured_link.as_str() {
+            "" => println!(
+                "[dry-run] {}: would remove the featured link from topic {}",
+                discourse_name, topic_id
+            ),
+            link => println!(
+                "[dry-run] {}: would set the featured link on topic {} to {}",
+                discourse_name, topic_id, link
+            ),
+        }
+    }
+}
+
 pub fn topic_reply(
     config: &Config,
     discourse_name: &str,
@@ -163,10 +888,15 @@ pub fn topic_new(
     if title.trim().is_empty() {
         return Err(anyhow!("topic title is empty"));
     }
-    let raw = read_reply_input(local_path)?;
+    let (_, raw) = parse_front_matter(&read_reply_input(local_path)?)?;
     if raw.trim().is_empty() {
         return Err(anyhow!("topic body is empty"));
     }
+    let base_dir = match local_path {
+        Some(path) if path.as_os_str() != "-" => markdown_dir(path),
+        _ => std::env::current_dir()?,
+    };
+    let raw = upload_local_assets(&client, &discourse.name, &base_dir, &raw, dry_run)?;
 
     if dry_run {
         println!(
@@ -184,6 +914,544 @@ pub fn topic_new(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
+pub fn topic_edit(
+    config: &Config,
+    discourse_name: &str,
+    topic_id: u64,
+    title: Option<&str>,
+    category: Option<&str>,
+    tags: Option<Vec<String>>,
+    featured_link: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = match category {
+        Some(category) => Some(resolve_category_id(&client, category)?),
+        None => None,
+    };
+    let update = topic_edit_update(title, category_id, tags, featured_link)?;
+    if dry_run {
+        print_topic_update(&discourse.name, topic_id, &update);
+        return Ok(());
+    }
+    client.update_topic(topic_id, &update)?;
+    println!("Topic {} updated", topic_id);
+    Ok(())
+}
+
+/// Build the update for `topic edit`, trimming values and dropping empty
+/// titles and tags. Errors when nothing would change.
+fn topic_edit_update(
+    title: Option<&str>,
+    category_id: Option<u64>,
+    tags: Option<Vec<String>>,
+    featured_link: Option<&str>,
+) -> Result<TopicUpdate> {
+    let title = title.map(str::trim).filter(|title| !title.is_empty());
+    let update = TopicUpdate {
+        title: title.map(str::to_string),
+        category_id,
+        tags: tags.map(|tags| {
+            tags.into_iter()
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty())
+                .collect()
+        }),
+        featured_link: featured_link.map(|link| link.trim().to_string()),
+    };
+    if update.is_empty() {
+        return Err(anyhow!(
+            "nothing to change; pass --title, --category, --tags or --featured-link"
+        ));
+    }
+    Ok(update)
+}
+
+pub fn topic_change_owner(
+    config: &Config,
+    discourse_name: &str,
+    topic_id: u64,
+    username: &str,
+    post_ids: &[u64],
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let post_ids = if post_ids.is_empty() {
+        let topic = client.fetch_topic(topic_id, false)?;
+        let post = topic
+            .post_stream
+            .posts
+            .first()
+            .ok_or_else(|| anyhow!("topic has no posts"))?;
+        vec![post.id]
+    } else {
+        post_ids.to_vec()
+    };
+    let post_list = post_id_list(&post_ids);
+    if dry_run {
+        println!(
+            "[dry-run] {}: would make {} the owner of post(s) {} in topic {}",
+            discourse.name, username, post_list, topic_id
+        );
+        return Ok(());
+    }
+    client.change_post_owner(topic_id, username, &post_ids)?;
+    println!(
+        "Topic {}: post(s) {} now owned by {}",
+        topic_id, post_list, username
+    );
+    Ok(())
+}
+
+fn post_id_list(post_ids: &[u64]) -> String {
+    post_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn topic_copy(
+    config: &Config,
+    source: &str,
+    topic_id: u64,
+    target: Option<&str>,
+    category: &str,
+    dry_run: bool,
+) -> Result<()> {
+    let source_discourse = select_discourse(config, Some(source))?;
+    let target_name = target.unwrap_or(source);
+    let target_discourse = select_discourse(config, Some(target_name))?;
+    ensure_api_credentials(source_discourse)?;
+    ensure_api_credentials(target_discourse)?;
+    let source_client = DiscourseClient::new(source_discourse)?;
+    let target_client = DiscourseClient::new(target_discourse)?;
+    let category_id = resolve_category_id(&target_client, category)?;
+
+    let topic = source_client.fetch_topic(topic_id, true)?;
+    let title = topic
+        .title
+        .clone()
+        .ok_or_else(|| anyhow!("topic {} has no title", topic_id))?;
+    let posts: Vec<Post> = source_client
+        .fetch_topic_posts(&topic)?
+        .into_iter()
+        .filter(is_copyable_post)
+        .collect();
+    if posts.is_empty() {
+        return Err(anyhow!("topic {} has no posts to copy", topic_id));
+    }
+
+    // Authors who also exist on the target post as themselves; the rest are
+    // credited in a quote header on a post by the API user.
+    let mut on_target: HashMap<String, bool> = HashMap::new();
+    for username in posts.iter().filter_map(|post| post.username.as_deref()) {
+        if !on_target.contains_key(username) {
+            let exists = target_client.user_exists(username)?;
+            on_target.insert(username.to_string(), exists);
+        }
+    }
+    if dry_run {
+        println!(
+            "[dry-run] would copy topic {} \"{}\" ({} post(s)) from {} to {} in category {}",
+            topic_id,
+            title,
+            posts.len(),
+            source_discourse.name,
+            target_discourse.name,
+            category
+        );
+        for post in &posts {
+            let username = post.username.as_deref().unwrap_or("unknown");
+            let author = match author_on_target(post, &on_target) {
+                Some(_) => format!("posted as {}", username),
+                None => format!("credited to {} in a quote header", username),
+            };
+            let uploads = upload_links(post.raw.as_deref().unwrap_or("")).len();
+            println!(
+                "  #{}: {}, {} upload(s) to copy",
+                post.post_number.unwrap_or(0),
+                author,
+                uploads
+            );
+        }
+        if !topic.tags.is_empty() {
+            println!("  tags: {}", topic.tags.join(", "));
+        }
+        return Ok(());
+    }
+
+    let bar = ProgressBar::new(posts.len() as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    let mut copied_uploads = HashMap::new();
+    let mut new_topic_id = None;
+    for post in &posts {
+        let raw = copy_uploads(
+            &source_client,
+            &target_client,
+            post.raw.as_deref().unwrap_or(""),
+            &mut copied_uploads,
+        )?;
+        let author = author_on_target(post, &on_target);
+        let raw = match (author, post.username.as_deref()) {
+            (None, Some(username)) => credit_author(username, &raw),
+            _ => raw,
+        };
+        let created_at = post.created_at.as_deref();
+        match new_topic_id {
+            None => {
+                let id =
+                    target_client.create_topic_as(category_id, &title, &raw, author, created_at)?;
+                new_topic_id = Some(id);
+            }
+            Some(id) => {
+                target_client.create_post_as(id, &raw, author, created_at)?;
+            }
+        }
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    let new_topic_id = new_topic_id.ok_or_else(|| anyhow!("no posts were copied"))?;
+    if !topic.tags.is_empty() {
+        target_client.set_topic_tags(new_topic_id, &topic.tags)?;
+    }
+    println!(
+        "Copied topic {} ({} post(s)) to {}/t/{}",
+        topic_id,
+        posts.len(),
+        target_client.baseurl(),
+        new_topic_id
+    );
+    Ok(())
+}
+
+/// Regular posts and moderator posts carry content worth copying; small
+/// actions ("closed this topic") and whispers don't.
+fn is_copyable_post(post: &Post) -> bool {
+    matches!(post.post_type, None | Some(1) | Some(2))
+        && post
+            .raw
+            .as_deref()
+            .is_some_and(|raw| !raw.trim().is_empty())
+}
+
+/// The post's author, if they have an account on the target.
+fn author_on_target<'a>(post: &'a Post, on_target: &HashMap<String, bool>) -> Option<&'a str> {
+    post.username
+        .as_deref()
+        .filter(|username| on_target.get(*username).copied().unwrap_or(false))
+}
+
+/// Wrap a post's body in a quote naming its original author.
+fn credit_author(username: &str, raw: &str) -> String {
+    format!("[quote=\"{}\"]\n{}\n[/quote]\n", username, raw.trim_end())
+}
+
+/// Topics per `/topics/bulk` request.
+const BULK_BATCH_SIZE: usize = 100;
+
+#[allow(clippy::too_many_arguments)]
+pub fn topic_bulk(
+    config: &Config,
+    discourse_name: &str,
+    query: &str,
+    action: BulkAction,
+    tags: &[String],
+    category: Option<&str>,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    let operation = match action {
+        BulkAction::AddTags => BulkOperation::AppendTags(tags.clone()),
+        BulkAction::RemoveTags => BulkOperation::RemoveTags(tags.clone()),
+        BulkAction::Move => {
+            let category = category.ok_or_else(|| anyhow!("--category is required to move"))?;
+            BulkOperation::ChangeCategory(resolve_category_id(&client, category)?)
+        }
+        BulkAction::Close => BulkOperation::Close,
+        BulkAction::Archive => BulkOperation::Archive,
+        BulkAction::Unlist => BulkOperation::Unlist,
+        BulkAction::Delete => BulkOperation::Delete,
+    };
+    if matches!(action, BulkAction::AddTags | BulkAction::RemoveTags) && tags.is_empty() {
+        return Err(anyhow!("--tags is required to add or remove tags"));
+    }
+
+    let hits = client.search_all_topics(query)?;
+    if hits.is_empty() {
+        println!("No topics match.");
+        return Ok(());
+    }
+    let id_width = hits
+        .iter()
+        .map(|h| h.id.to_string().len())
+        .max()
+        .unwrap_or(2);
+    for hit in &hits {
+        println!("{:>width$}  {}", hit.id, hit.title, width = id_width);
+    }
+    println!("{} topic(s) match.", hits.len());
+
+    let description = match &operation {
+        BulkOperation::AppendTags(tags) => format!("add tags [{}] to", tags.join(", ")),
+        BulkOperation::RemoveTags(tags) => format!("remove tags [{}] from", tags.join(", ")),
+        BulkOperation::ChangeCategory(category_id) => {
+            format!("move to category {}", category_id)
+        }
+        BulkOperation::Close => "close".to_string(),
+        BulkOperation::Archive => "archive".to_string(),
+        BulkOperation::Unlist => "unlist".to_string(),
+        BulkOperation::Delete => "delete".to_string(),
+    };
+    let prompt = format!("{} {} topic(s)", description, hits.len());
+    if dry_run {
+        println!("[dry-run] {}: would {}", discourse.name, prompt);
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&prompt)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let bar = ProgressBar::new(hits.len() as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    let mut changed = 0usize;
+    let ids: Vec<u64> = hits.iter().map(|hit| hit.id).collect();
+    match &operation {
+        BulkOperation::RemoveTags(tags) => {
+            let mut bulk_done = 0;
+            for batch in ids.chunks(BULK_BATCH_SIZE) {
+                let Some(ids) = client.bulk_remove_topic_tags(batch, tags)? else {
+                    break;
+                };
+                changed += ids.len();
+                bulk_done += batch.len();
+                bar.inc(batch.len() as u64);
+            }
+            // Older `/topics/bulk` can only strip every tag, so drop the
+            // chosen ones topic by topic.
+            if bulk_done < hits.len() {
+                bar.println(
+                    "This Discourse can't remove tags in bulk; editing each topic instead.",
+                );
+            }
+            for hit in &hits[bulk_done..] {
+                bar.set_message(hit.title.clone());
+                bar.inc(1);
+                let current = match &hit.tags {
+                    Some(current) => current.clone(),
+                    None => client.fetch_topic_tags(hit.id)?,
+                };
+                let next: Vec<String> = current
+                    .iter()
+                    .filter(|tag| !tags.contains(tag))
+                    .cloned()
+                    .collect();
+                if next.len() != current.len() {
+                    client.set_topic_tags(hit.id, &next)?;
+                    changed += 1;
+                }
+            }
+        }
+        operation => {
+            for batch in ids.chunks(BULK_BATCH_SIZE) {
+                changed += client.bulk_update_topics(batch, operation)?.len();
+                bar.inc(batch.len() as u64);
+            }
+        }
+    }
+    bar.finish_and_clear();
+    println!("Done: {} {} topic(s)", description, changed);
+    Ok(())
+}
+
+/// A status change made by the topic lifecycle commands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TopicStatusChange {
+    Close,
+    Open,
+    Pin { global: bool },
+    Unpin,
+    Archive,
+    Unarchive,
+    Unlist,
+    Relist,
+}
+
+impl TopicStatusChange {
+    /// The Discourse status flag and the value to set it to.
+    fn flag(self) -> (&'static str, bool) {
+        match self {
+            Self::Close => ("closed", true),
+            Self::Open => ("closed", false),
+            Self::Pin { global: true } => ("pinned_globally", true),
+            Self::Pin { global: false } => ("pinned", true),
+            Self::Unpin => ("pinned", false),
+            Self::Archive => ("archived", true),
+            Self::Unarchive => ("archived", false),
+            Self::Unlist => ("visible", false),
+            Self::Relist => ("visible", true),
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Close => "close",
+            Self::Open => "open",
+            Self::Pin { global: true } => "pin globally",
+            Self::Pin { global: false } => "pin",
+            Self::Unpin => "unpin",
+            Self::Archive => "archive",
+            Self::Unarchive => "unarchive",
+            Self::Unlist => "unlist",
+            Self::Relist => "relist",
+        }
+    }
+
+    fn past_tense(self) -> &'static str {
+        match self {
+            Self::Close => "Closed",
+            Self::Open => "Opened",
+            Self::Pin { global: true } => "Pinned globally",
+            Self::Pin { global: false } => "Pinned",
+            Self::Unpin => "Unpinned",
+            Self::Archive => "Archived",
+            Self::Unarchive => "Unarchived",
+            Self::Unlist => "Unlisted",
+            Self::Relist => "Relisted",
+        }
+    }
+}
+
+pub fn topic_set_status(
+    config: &Config,
+    discourse_name: &str,
+    topic_ids: &[u64],
+    change: TopicStatusChange,
+    until: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let (status, enabled) = change.flag();
+    let until_note = until.map(|u| format!(" until {}", u)).unwrap_or_default();
+
+    for_each_topic(topic_ids, |topic_id| {
+        if dry_run {
+            println!(
+                "[dry-run] {}: would {} topic {}{}",
+                discourse.name,
+                change.verb(),
+                topic_id,
+                until_note
+            );
+            return Ok(());
+        }
+        client.set_topic_status(topic_id, status, enabled, until)?;
+        println!("{} topic {}{}", change.past_tense(), topic_id, until_note);
+        Ok(())
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn topic_timer(
+    config: &Config,
+    discourse_name: &str,
+    topic_ids: &[u64],
+    action: TimerAction,
+    time: Option<&str>,
+    based_on_last_post: bool,
+    category: Option<&str>,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let category_id = match category {
+        Some(category) => Some(resolve_category_id(&client, category)?),
+        None => None,
+    };
+    let timer = TopicTimer {
+        status_type: timer_status_type(action).to_string(),
+        time: time.map(str::to_string),
+        based_on_last_post,
+        category_id,
+    };
+    let description = match (&timer.time, category_id) {
+        (None, _) => format!("clear the {} timer on", timer.status_type),
+        (Some(time), Some(category_id)) => format!(
+            "set a {} timer ({}, category {}) on",
+            timer.status_type, time, category_id
+        ),
+        (Some(time), None) => format!("set a {} timer ({}) on", timer.status_type, time),
+    };
+
+    for_each_topic(topic_ids, |topic_id| {
+        if dry_run {
+            println!(
+                "[dry-run] {}: would {} topic {}",
+                discourse.name, description, topic_id
+            );
+            return Ok(());
+        }
+        client.set_topic_timer(topic_id, &timer)?;
+        match &timer.time {
+            Some(time) => println!(
+                "Topic {}: {} timer set ({})",
+                topic_id, timer.status_type, time
+            ),
+            None => println!("Topic {}: {} timer cleared", topic_id, timer.status_type),
+        }
+        Ok(())
+    })
+}
+
+fn timer_status_type(action: TimerAction) -> &'static str {
+    match action {
+        TimerAction::Close => "close",
+        TimerAction::Open => "open",
+        TimerAction::Delete => "delete",
+        TimerAction::PublishToCategory => "publish_to_category",
+    }
+}
+
+/// Run `action` on each topic, reporting failures and carrying on so one
+/// bad ID doesn't stop the rest of a batch.
+fn for_each_topic(topic_ids: &[u64], mut action: impl FnMut(u64) -> Result<()>) -> Result<()> {
+    let mut failed = 0;
+    for &topic_id in topic_ids {
+        if let Err(err) = action(topic_id) {
+            eprintln!("Topic {}: {:#}", topic_id, err);
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!("{} of {} topic(s) failed", failed, topic_ids.len()));
+    }
+    Ok(())
+}
+
 fn read_reply_input(local_path: Option<&Path>) -> Result<String> {
     let from_stdin = match local_path {
         None => true,
@@ -203,10 +1471,155 @@ fn read_reply_input(local_path: Option<&Path>) -> Result<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::read_reply_input;
+    use super::{
+        SyncBase, TopicStatusChange, changed_topic_fields, collect_markdown_files, credit_author,
+        is_copyable_post, post_id_list, read_reply_input, read_sync_base, render_thread,
+        sync_base_path, topic_edit_update, topic_front_matter, topic_update_from_front_matter,
+        write_post_files, write_sync_base,
+    };
+    use crate::api::{DiscourseClient, Post, TopicResponse};
+    use crate::commands::assets::PullAssets;
+    use crate::config::DiscourseConfig;
+    use crate::frontmatter::{FrontMatter, parse_front_matter, read_topic_file};
+    use std::collections::HashMap;
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    fn sample_topic() -> TopicResponse {
+        serde_json::from_str(
+            r#"{"id": 42, "title": "Hello", "category_id": 5, "tags": ["a", "b"],
+                "post_stream": {"posts": [{"id": 99, "updated_at": "2026-01-01T00:00:00Z"}]}}"#,
+        )
+        .unwrap()
+    }
+
+    fn slugs() -> HashMap<u64, String> {
+        HashMap::from([(5, "docs".to_string()), (6, "news".to_string())])
+    }
+
+    #[test]
+    fn front_matter_records_topic_identity() {
+        let fm = topic_front_matter("myforum", &sample_topic(), &slugs());
+        assert_eq!(fm.topic_id, Some(42));
+        assert_eq!(fm.post_id, Some(99));
+        assert_eq!(fm.category.as_deref(), Some("docs"));
+        assert_eq!(fm.tags, Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(fm.updated_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+    }
+
+    #[test]
+    fn unchanged_front_matter_produces_empty_update() {
+        let topic = sample_topic();
+        let fm = topic_front_matter("myforum", &topic, &slugs());
+        let update = topic_update_from_front_matter(&fm, &topic, &slugs()).unwrap();
+        assert!(update.is_empty());
+    }
+
+    #[test]
+    fn changed_front_matter_produces_only_changed_fields() {
+        let fm = FrontMatter {
+            title: Some("Hello".to_string()),
+            category: Some("news".to_string()),
+            tags: Some(vec!["b".to_string(), "a".to_string()]),
+            ..FrontMatter::default()
+        };
+        let update = topic_update_from_front_matter(&fm, &sample_topic(), &slugs()).unwrap();
+        assert_eq!(update.title, None);
+        assert_eq!(update.category_id, Some(6));
+        assert_eq!(update.tags, None);
+        assert_eq!(changed_topic_fields(&update), vec!["category"]);
+    }
+
+    #[test]
+    fn unknown_category_slug_is_an_error() {
+        let fm = FrontMatter {
+            category: Some("missing".to_string()),
+            ..FrontMatter::default()
+        };
+        assert!(topic_update_from_front_matter(&fm, &sample_topic(), &slugs()).is_err());
+    }
+
+    fn sample_posts() -> Vec<Post> {
+        serde_json::from_str(
+            r#"[{"id": 99, "post_number": 1, "username": "alice", "raw": "Opening\n",
+                 "created_at": "2026-01-01T00:00:00Z"},
+                {"id": 100, "post_number": 2, "username": "bob", "raw": "Reply",
+                 "created_at": "2026-01-02T00:00:00Z"}]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn thread_archive_has_a_header_per_post() {
+        let fm = topic_front_matter("myforum", &sample_topic(), &slugs());
+        let rendered = render_thread(&fm, &sample_posts()).unwrap();
+        let (fm, body) = parse_front_matter(&rendered).unwrap();
+        let fm = fm.unwrap();
+        assert_eq!(fm.posts, Some(2));
+        assert_eq!(fm.post_id, None);
+        assert_eq!(
+            body,
+            "## Post 1 by @alice on 2026-01-01T00:00:00Z\n\nOpening\n\n\
+             ## Post 2 by @bob on 2026-01-02T00:00:00Z\n\nReply\n"
+        );
+    }
+
+    #[test]
+    fn split_thread_writes_one_file_per_post() {
+        let dir = tempfile::tempdir().unwrap();
+        let fm = topic_front_matter("myforum", &sample_topic(), &slugs());
+        let client = DiscourseClient::new(&DiscourseConfig {
+            baseurl: "https://forum.example.com".to_string(),
+            ..DiscourseConfig::default()
+        })
+        .unwrap();
+        let assets = PullAssets::new(&client, "myforum", false);
+        write_post_files(dir.path(), &fm, &sample_posts(), &assets).unwrap();
+
+        let (opening, raw) = read_topic_file(&dir.path().join("001-alice.md")).unwrap();
+        let opening = opening.unwrap();
+        assert_eq!(opening.title.as_deref(), Some("Hello"));
+        assert_eq!(opening.post_number, Some(1));
+        assert_eq!(raw, "Opening\n");
+        assert!(
+            read_sync_base(&dir.path().join("001-alice.md"))
+                .unwrap()
+                .is_some()
+        );
+
+        let (reply, raw) = read_topic_file(&dir.path().join("002-bob.md")).unwrap();
+        let reply = reply.unwrap();
+        assert_eq!(reply.topic_id, Some(42));
+        assert_eq!(reply.post_id, Some(100));
+        assert_eq!(reply.author.as_deref(), Some("bob"));
+        assert_eq!(reply.title, None);
+        assert_eq!(raw, "Reply");
+    }
+
+    #[test]
+    fn sync_base_sits_beside_the_file() {
+        let path = std::path::Path::new("docs/intro.md");
+        assert_eq!(
+            sync_base_path(path),
+            std::path::Path::new("docs/.intro.md.dsc-base")
+        );
+    }
+
+    #[test]
+    fn sync_base_roundtrips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("topic.md");
+        assert_eq!(read_sync_base(&path).unwrap(), None);
+        let base = SyncBase {
+            topic_id: 42,
+            post_id: 99,
+            revision: Some(3),
+            raw: "line one\nline two\n".to_string(),
+        };
+        write_sync_base(&path, &base).unwrap();
+        assert_eq!(read_sync_base(&path).unwrap(), Some(base));
+    }
+
     #[test]
     fn read_reply_input_reads_from_file() {
         let mut f = NamedTempFile::new().unwrap();
@@ -222,17 +1635,95 @@ mod tests {
         let msg = format!("{:#}", err);
         assert!(msg.contains("/definitely/does/not/exist.md"));
     }
-}
 
-fn confirm_sync(pull: bool) -> Result<bool> {
-    let action = if pull {
-        "pull from Discourse"
-    } else {
-        "push to Discourse"
-    };
-    print!("Proceed to {}? [y/N]: ", action);
-    io::stdout().flush()?;
-    let mut input = String::new();
-    io::stdin().read_line(&mut input)?;
-    Ok(matches!(input.trim(), "y" | "Y" | "yes" | "YES"))
+    #[test]
+    fn status_changes_map_to_discourse_flags() {
+        assert_eq!(TopicStatusChange::Open.flag(), ("closed", false));
+        assert_eq!(
+            TopicStatusChange::Pin { global: true }.flag(),
+            ("pinned_globally", true)
+        );
+        assert_eq!(TopicStatusChange::Unpin.flag(), ("pinned", false));
+        assert_eq!(TopicStatusChange::Relist.flag(), ("visible", true));
+    }
+
+    #[test]
+    fn diff_walks_markdown_skipping_assets_and_hidden_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["guides", "assets", ".git"] {
+            std::fs::create_dir(root.join(sub)).unwrap();
+            std::fs::write(root.join(sub).join("a.md"), "# A\n").unwrap();
+        }
+        std::fs::write(root.join("b.md"), "# B\n").unwrap();
+        std::fs::write(root.join("notes.txt"), "x").unwrap();
+        let mut files = Vec::new();
+        collect_markdown_files(root, &mut files).unwrap();
+        assert_eq!(
+            files,
+            vec![root.join("b.md"), root.join("guides").join("a.md")]
+        );
+    }
+
+    #[test]
+    fn copy_skips_small_actions_and_whispers() {
+        let posts: Vec<Post> = serde_json::from_str(
+            r#"[
+                {"id": 1, "post_number": 1, "post_type": 1, "raw": "Opening"},
+                {"id": 2, "post_number": 2, "post_type": 3, "raw": ""},
+                {"id": 3, "post_number": 3, "post_type": 4, "raw": "Staff only"},
+                {"id": 4, "post_number": 4, "post_type": 2, "raw": "Moderator note"}
+            ]"#,
+        )
+        .unwrap();
+        let copied: Vec<u64> = posts
+            .iter()
+            .filter(|post| is_copyable_post(post))
+            .map(|post| post.id)
+            .collect();
+        assert_eq!(copied, vec![1, 4]);
+    }
+
+    #[test]
+    fn unmatched_authors_are_credited_in_a_quote() {
+        assert_eq!(
+            credit_author("alice", "Hello\n\n"),
+            "[quote=\"alice\"]\nHello\n[/quote]\n"
+        );
+    }
+
+    #[test]
+    fn topic_edit_trims_and_drops_empty_values() {
+        let update = topic_edit_update(
+            Some("  New title "),
+            Some(4),
+            Some(vec![" a ".to_string(), "  ".to_string(), "b".to_string()]),
+            Some(" https://example.com "),
+        )
+        .expect("update");
+        assert_eq!(update.title.as_deref(), Some("New title"));
+        assert_eq!(update.category_id, Some(4));
+        assert_eq!(update.tags, Some(vec!["a".to_string(), "b".to_string()]));
+        assert_eq!(update.featured_link.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn topic_edit_keeps_empty_tag_list_to_clear_tags() {
+        let update =
+            topic_edit_update(None, None, Some(vec![" ".to_string()]), None).expect("update");
+        assert_eq!(update.tags, Some(Vec::new()));
+        assert!(update.title.is_none());
+    }
+
+    #[test]
+    fn topic_edit_without_changes_is_an_error() {
+        let err = topic_edit_update(Some("  "), None, None, None).unwrap_err();
+        assert!(err.to_string().contains("nothing to change"));
+    }
+
+    #[test]
+    fn post_id_list_joins_ids() {
+        assert_eq!(post_id_list(&[3]), "3");
+        assert_eq!(post_id_list(&[3, 5, 8]), "3, 5, 8");
+    }
 }
@@ -1,25 +1,43 @@
-use crate::api::{DiscourseClient, UserAction};
-use crate::cli::ListFormat;
-use crate::commands::common::{ensure_api_credentials, select_discourse};
+use crate::api::{DeleteUserOptions, DiscourseClient, UserAction, UserSummary};
+use crate::cli::{ListFormat, UserColumn, UserFilterArgs, UserListFormat};
+use crate::commands::common::{
+    confirm_action, confirm_typed, ensure_api_credentials, not_found, select_discourse,
+};
 use crate::config::Config;
 use crate::utils::{normalize_baseurl, parse_since_cutoff};
 use anyhow::{Context, Result, anyhow};
-use std::io::{self, Read};
-
+use chrono::{DateTime, Utc};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+#[allow(clippy::too_many_arguments)]
 pub fn user_list(
     config: &Config,
     discourse_name: &str,
     listing: &str,
     page: u32,
-    format: ListFormat,
+    all: bool,
+    filters: &UserFilterArgs,
+    format: UserListFormat,
+    columns: &[UserColumn],
 ) -> Result<()> {
     let discourse = select_discourse(config, Some(discourse_name))?;
     ensure_api_credentials(discourse)?;
     let client = DiscourseClient::new(discourse)?;
-    let users = client.admin_list_users(listing, page)?;
+    let filter = UserFilter::from_args(&client, filters)?;
+    let users = if all {
+        client.admin_list_all_users(listing)?
+    } else {
+        client.admin_list_users(listing, page)?
+    };
+    let users: Vec<UserSummary> = users.into_iter().filter(|u| filter.matches(u)).collect();
 
     match format {
-        ListFormat::Text => {
+        UserListFormat::Text => {
             if users.is_empty() {
                 println!("No users found in listing '{}'.", listing);
                 return Ok(());
@@ -56,17 +74,177 @@ pub fn user_list(
                 );
             }
         }
-        ListFormat::Json => {
+        UserListFormat::Json => {
             println!("{}", serde_json::to_string_pretty(&users)?);
         }
-        ListFormat::Yaml => {
+        UserListFormat::Yaml => {
             println!("{}", serde_yaml::to_string(&users)?);
         }
+        UserListFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(io::stdout());
+            write_users_csv(&users, columns, &mut writer)?;
+            writer.flush()?;
+        }
+        UserListFormat::MarkdownTable => print!("{}", render_users_markdown(&users, columns)),
     }
 
     Ok(())
 }
 
+/// Which users `user list` keeps.
+#[derive(Debug, Default)]
+struct UserFilter {
+    trust_levels: Vec<u64>,
+    last_seen_before: Option<DateTime<Utc>>,
+    created_after: Option<DateTime<Utc>>,
+    /// Lowercase, without the `@`.
+    email_domains: Vec<String>,
+    /// Lowercase usernames of the `--group` members.
+    members: Option<HashSet<String>>,
+}
+
+impl UserFilter {
+    fn from_args(client: &DiscourseClient, args: &UserFilterArgs) -> Result<Self> {
+        let cutoff = |raw: &Option<String>| raw.as_deref().map(parse_since_cutoff).transpose();
+        let members = match &args.group {
+            Some(group) => Some(group_usernames(client, group)?),
+            None => None,
+        };
+        Ok(Self {
+            trust_levels: args.trust_level.clone(),
+            last_seen_before: cutoff(&args.last_seen_before)?,
+            created_after: cutoff(&args.created_after)?,
+            email_domains: args
+                .email_domain
+                .iter()
+                .map(|domain| domain.trim().trim_start_matches('@').to_ascii_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .collect(),
+            members,
+        })
+    }
+
+    fn matches(&self, user: &UserSummary) -> bool {
+        if !self.trust_levels.is_empty()
+            && !user
+                .trust_level
+                .is_some_and(|level| self.trust_levels.contains(&level))
+        {
+            return false;
+        }
+        if let Some(cutoff) = self.last_seen_before
+            && parse_timestamp(user.last_seen_at.as_deref()).is_some_and(|seen| seen >= cutoff)
+        {
+            return false;
+        }
+        if let Some(cutoff) = self.created_after
+            && parse_timestamp(user.created_at.as_deref()).is_none_or(|created| created <= cutoff)
+        {
+            return false;
+        }
+        if !self.email_domains.is_empty() {
+            let domain = user
+                .email
+                .as_deref()
+                .and_then(|email| email.rsplit_once('@'))
+                .map(|(_, domain)| domain.to_ascii_lowercase());
+            if !domain.is_some_and(|domain| self.email_domains.contains(&domain)) {
+                return false;
+            }
+        }
+        if let Some(members) = &self.members
+            && !members.contains(&user.username.to_ascii_lowercase())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+fn parse_timestamp(raw: Option<&str>) -> Option<DateTime<Utc>> {
+    raw.and_then(|raw| DateTime::parse_from_rfc3339(raw).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Lowercase usernames of a group's members and owners; `group` is a name
+/// or ID.
+fn group_usernames(client: &DiscourseClient, group: &str) -> Result<HashSet<String>> {
+    let groups = client.fetch_groups()?;
+    let found = groups
+        .iter()
+        .find(|g| g.id.to_string() == group || g.name.eq_ignore_ascii_case(group))
+        .ok_or_else(|| not_found("group", group))?;
+    let membership = client.fetch_group_membership(found.id, Some(&found.name))?;
+    Ok(membership
+        .members
+        .iter()
+        .chain(&membership.owners)
+        .map(|member| member.username.to_ascii_lowercase())
+        .collect())
+}
+
+fn user_column_name(column: UserColumn) -> &'static str {
+    match column {
+        UserColumn::Id => "id",
+        UserColumn::Username => "username",
+        UserColumn::Name => "name",
+        UserColumn::Email => "email",
+        UserColumn::TrustLevel => "trust_level",
+        UserColumn::Admin => "admin",
+        UserColumn::Moderator => "moderator",
+        UserColumn::Suspended => "suspended",
+        UserColumn::Silenced => "silenced",
+        UserColumn::LastSeenAt => "last_seen_at",
+        UserColumn::CreatedAt => "created_at",
+        UserColumn::PostCount => "post_count",
+    }
+}
+
+fn user_column_value(user: &UserSummary, column: UserColumn) -> String {
+    let flag = |value: Option<bool>| value.unwrap_or(false).to_string();
+    let number = |value: Option<u64>| value.map(|n| n.to_string()).unwrap_or_default();
+    match column {
+        UserColumn::Id => user.id.to_string(),
+        UserColumn::Username => user.username.clone(),
+        UserColumn::Name => user.name.clone().unwrap_or_default(),
+        UserColumn::Email => user.email.clone().unwrap_or_default(),
+        UserColumn::TrustLevel => number(user.trust_level),
+        UserColumn::Admin => flag(user.admin),
+        UserColumn::Moderator => flag(user.moderator),
+        UserColumn::Suspended => flag(user.suspended),
+        UserColumn::Silenced => flag(user.silenced),
+        UserColumn::LastSeenAt => user.last_seen_at.clone().unwrap_or_default(),
+        UserColumn::CreatedAt => user.created_at.clone().unwrap_or_default(),
+        UserColumn::PostCount => number(user.post_count),
+    }
+}
+
+fn write_users_csv<W: Write>(
+    users: &[UserSummary],
+    columns: &[UserColumn],
+    writer: &mut csv::Writer<W>,
+) -> Result<()> {
+    writer.write_record(columns.iter().map(|c| user_column_name(*c)))?;
+    for user in users {
+        writer.write_record(columns.iter().map(|c| user_column_value(user, *c)))?;
+    }
+    Ok(())
+}
+
+fn render_users_markdown(users: &[UserSummary], columns: &[UserColumn]) -> String {
+    let names: Vec<&str> = columns.iter().map(|c| user_column_name(*c)).collect();
+    let mut out = format!("| {} |\n", names.join(" | "));
+    out.push_str(&format!("|{}\n", " --- |".repeat(columns.len())));
+    for user in users {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|c| user_column_value(user, *c).replace('|', "\\|"))
+            .collect();
+        out.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+    out
+}
+
 pub fn user_info(
     config: &Config,
     discourse_name: &str,
@@ -233,6 +411,517 @@ pub fn user_unsilence(
     Ok(())
 }
 
+/// Apply the moderation actions in a CSV file. Every row is checked before
+/// any is acted on, and each result is written to a report; rows the report
+/// already marks done are skipped, so rerunning resumes a partial run.
+pub fn user_bulk(
+    config: &Config,
+    discourse_name: &str,
+    local_path: &Path,
+    report_path: Option<&Path>,
+    delete_posts: bool,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let raw = fs::read_to_string(local_path)
+        .with_context(|| format!("reading {}", local_path.display()))?;
+    let rows = parse_bulk_file(&raw)?;
+    if rows.is_empty() {
+        return Err(anyhow!("no rows found in {}", local_path.display()));
+    }
+    let report_path = report_path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| local_path.with_extension("report.csv"));
+    let done = load_bulk_report(&report_path)?;
+    let (rows, skipped): (Vec<BulkRow>, Vec<BulkRow>) = rows
+        .into_iter()
+        .partition(|row| !done.iter().any(|entry| entry.matches(row)));
+    if !skipped.is_empty() {
+        println!(
+            "Skipping {} row(s) already done according to {}",
+            skipped.len(),
+            report_path.display()
+        );
+    }
+    if rows.is_empty() {
+        println!("Nothing to do.");
+        return Ok(());
+    }
+
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+    let mut tasks = Vec::new();
+    let mut problems = Vec::new();
+    for row in rows {
+        match validate_bulk_row(&client, &row, &tasks) {
+            Ok(task) => tasks.push(task),
+            Err(err) => problems.push(format!("line {} ({}): {:#}", row.line, row.login, err)),
+        }
+    }
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{}", problem);
+        }
+        return Err(anyhow!(
+            "{} invalid row(s) in {}; nothing was changed",
+            problems.len(),
+            local_path.display()
+        ));
+    }
+
+    print_bulk_tasks(&tasks);
+    let deletes = tasks
+        .iter()
+        .filter(|task| task.action == BulkUserAction::Delete)
+        .count();
+    let mut prompt = format!(
+        "apply {} action(s) to users on {}",
+        tasks.len(),
+        discourse.name
+    );
+    if delete_posts && deletes > 0 {
+        prompt.push_str(&format!(", deleting the posts of {} user(s)", deletes));
+    }
+    let delete_options = DeleteUserOptions {
+        delete_posts,
+        ..DeleteUserOptions::default()
+    };
+    if dry_run {
+        println!("[dry-run] {}: would {}", discourse.name, prompt);
+        return Ok(());
+    }
+    if !assume_yes && !confirm_action(&prompt)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+
+    let mut report = csv::Writer::from_path(&report_path)
+        .with_context(|| format!("writing {}", report_path.display()))?;
+    report.write_record(["line", "user", "action", "status", "message"])?;
+    for entry in &done {
+        report.write_record([
+            entry.line.as_str(),
+            entry.login.as_str(),
+            entry.action.as_str(),
+            "done",
+            entry.message.as_str(),
+        ])?;
+    }
+    report.flush()?;
+
+    let bar = ProgressBar::new(tasks.len() as u64);
+    bar.set_style(
+        ProgressStyle::with_template("{bar:30} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+    let mut failures = Vec::new();
+    for task in &tasks {
+        bar.set_message(task.username.clone());
+        let (status, message) = match run_bulk_task(&client, task, &delete_options) {
+            Ok(()) => ("done", String::new()),
+            Err(err) => {
+                let message = format!("{:#}", err);
+                failures.push(format!(
+                    "line {} ({}): {}",
+                    task.row.line, task.row.login, message
+                ));
+                ("failed", message)
+            }
+        };
+        report.write_record([
+            task.row.line.to_string().as_str(),
+            task.row.login.as_str(),
+            task.action.label(),
+            status,
+            message.as_str(),
+        ])?;
+        report.flush()?;
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    println!(
+        "Done: {} succeeded, {} failed; results in {}",
+        tasks.len() - failures.len(),
+        failures.len(),
+        report_path.display()
+    );
+    if !failures.is_empty() {
+        for failure in &failures {
+            eprintln!("{}", failure);
+        }
+        return Err(anyhow!(
+            "{} action(s) failed; rerun to retry them",
+            failures.len()
+        ));
+    }
+    Ok(())
+}
+
+/// What `user bulk` does to a user.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BulkUserAction {
+    Suspend,
+    Silence,
+    Unsuspend,
+    Unsilence,
+    Delete,
+    Deactivate,
+}
+
+impl BulkUserAction {
+    const ALL: [Self; 6] = [
+        Self::Suspend,
+        Self::Silence,
+        Self::Unsuspend,
+        Self::Unsilence,
+        Self::Delete,
+        Self::Deactivate,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Suspend => "suspend",
+            Self::Silence => "silence",
+            Self::Unsuspend => "unsuspend",
+            Self::Unsilence => "unsilence",
+            Self::Delete => "delete",
+            Self::Deactivate => "deactivate",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.label().eq_ignore_ascii_case(raw))
+    }
+
+    /// Whether the `until` and `reason` columns mean anything.
+    fn takes_until(self) -> bool {
+        matches!(self, Self::Suspend | Self::Silence)
+    }
+}
+
+/// One row of a `user bulk` file.
+#[derive(Debug, Clone, PartialEq)]
+struct BulkRow {
+    line: u64,
+    /// Username or email address.
+    login: String,
+    action: String,
+    until: String,
+    reason: String,
+}
+
+/// A checked row, ready to run.
+#[derive(Debug)]
+struct BulkTask {
+    row: BulkRow,
+    action: BulkUserAction,
+    user_id: u64,
+    username: String,
+}
+
+/// A row a previous run finished, from its report.
+#[derive(Debug, Deserialize)]
+struct BulkReportEntry {
+    line: String,
+    #[serde(rename = "user")]
+    login: String,
+    action: String,
+    status: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl BulkReportEntry {
+    fn matches(&self, row: &BulkRow) -> bool {
+        self.login.eq_ignore_ascii_case(&row.login) && self.action.eq_ignore_ascii_case(&row.action)
+    }
+}
+
+/// Parse CSV lines of `user,action,until,reason`. A header row naming the
+/// first column, blank lines and `#` comments are skipped.
+fn parse_bulk_file(raw: &str) -> Result<Vec<BulkRow>> {
+    // Blank out comments rather than letting the reader skip them, so
+    // positions in the text are positions in the file.
+    let uncommented: Vec<&str> = raw
+        .lines()
+        .map(|line| {
+            if line.trim_start().starts_with('#') {
+                ""
+            } else {
+                line
+            }
+        })
+        .collect();
+    let uncommented = uncommented.join("\n");
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(uncommented.as_bytes());
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let login = record.get(0).unwrap_or("");
+        if login.is_empty() {
+            continue;
+        }
+        if index == 0
+            && matches!(
+                login.to_ascii_lowercase().as_str(),
+                "user" | "username" | "email" | "login"
+            )
+        {
+            continue;
+        }
+        // A record's position is where the reader started looking for it,
+        // before any blank lines, so count lines up to its first character.
+        let offset = record.position().map_or(0, |pos| pos.byte() as usize);
+        let rest = &uncommented[offset..];
+        let start = offset + rest.len() - rest.trim_start().len();
+        rows.push(BulkRow {
+            line: uncommented[..start].matches('\n').count() as u64 + 1,
+            login: login.to_string(),
+            action: record.get(1).unwrap_or("").to_string(),
+            until: record.get(2).unwrap_or("").to_string(),
+            reason: record.get(3).unwrap_or("").to_string(),
+        });
+    }
+    Ok(rows)
+}
+
+/// Rows an earlier run's report marks done, or none without a report.
+fn load_bulk_report(path: &Path) -> Result<Vec<BulkReportEntry>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut reader =
+        csv::Reader::from_path(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut done = Vec::new();
+    for entry in reader.deserialize() {
+        let entry: BulkReportEntry =
+            entry.with_context(|| format!("parsing {}", path.display()))?;
+        if entry.status == "done" {
+            done.push(entry);
+        }
+    }
+    Ok(done)
+}
+
+/// Check a row's action and dates and look up its user. `earlier` are the
+/// rows already checked, to catch duplicates.
+fn validate_bulk_row(
+    client: &DiscourseClient,
+    row: &BulkRow,
+    earlier: &[BulkTask],
+) -> Result<BulkTask> {
+    let action = BulkUserAction::parse(&row.action).ok_or_else(|| {
+        let known: Vec<&str> = BulkUserAction::ALL.iter().map(|a| a.label()).collect();
+        anyhow!(
+            "unknown action {:?} (expected one of {})",
+            row.action,
+            known.join(", ")
+        )
+    })?;
+    check_bulk_until(action, &row.until)?;
+    if let Some(first) = earlier
+        .iter()
+        .find(|task| task.action == action && task.row.login.eq_ignore_ascii_case(&row.login))
+    {
+        return Err(anyhow!("repeats line {}", first.row.line));
+    }
+    let username = if row.login.contains('@') {
+        client
+            .find_user_by_email(&row.login)?
+            .ok_or_else(|| anyhow!("no account has this email address"))?
+            .username
+    } else {
+        row.login.clone()
+    };
+    let detail = client.fetch_user_detail(&username)?;
+    Ok(BulkTask {
+        row: row.clone(),
+        action,
+        user_id: detail.id,
+        username: detail.username,
+    })
+}
+
+/// `until` must be empty, `forever`, or an ISO-8601 date or timestamp, and
+/// only suspend and silence take one.
+fn check_bulk_until(action: BulkUserAction, until: &str) -> Result<()> {
+    if until.is_empty() {
+        return Ok(());
+    }
+    if !action.takes_until() {
+        return Err(anyhow!("{} takes no until", action.label()));
+    }
+    let valid = (action == BulkUserAction::Suspend && until.eq_ignore_ascii_case("forever"))
+        || DateTime::parse_from_rfc3339(until).is_ok()
+        || chrono::NaiveDate::parse_from_str(until, "%Y-%m-%d").is_ok();
+    if !valid {
+        return Err(anyhow!(
+            "until must be an ISO-8601 date or timestamp{}, got {:?}",
+            if action == BulkUserAction::Suspend {
+                " or `forever`"
+            } else {
+                ""
+            },
+            until
+        ));
+    }
+    Ok(())
+}
+
+fn run_bulk_task(
+    client: &DiscourseClient,
+    task: &BulkTask,
+    delete_options: &DeleteUserOptions,
+) -> Result<()> {
+    let row = &task.row;
+    match task.action {
+        BulkUserAction::Suspend => {
+            let until = if row.until.is_empty() {
+                "forever"
+            } else {
+                &row.until
+            };
+            client.suspend_user(task.user_id, until, &row.reason)
+        }
+        BulkUserAction::Silence => client.silence_user(task.user_id, &row.until, &row.reason),
+        BulkUserAction::Unsuspend => client.unsuspend_user(task.user_id),
+        BulkUserAction::Unsilence => client.unsilence_user(task.user_id),
+        BulkUserAction::Delete => client.delete_user(task.user_id, delete_options),
+        BulkUserAction::Deactivate => client.deactivate_user(task.user_id),
+    }
+}
+
+fn print_bulk_tasks(tasks: &[BulkTask]) {
+    let name_width = tasks
+        .iter()
+        .map(|task| task.username.len())
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    for task in tasks {
+        let until = match task.action {
+            BulkUserAction::Suspend if task.row.until.is_empty() => "forever",
+            _ if task.row.until.is_empty() => "-",
+            _ => &task.row.until,
+        };
+        println!(
+            "{:>5}  {:<width$}  id:{:<7}  {:<10}  {:<20}  {}",
+            task.row.line,
+            task.username,
+            task.user_id,
+            task.action.label(),
+            until,
+            if task.row.reason.is_empty() {
+                "-"
+            } else {
+                &task.row.reason
+            },
+            width = name_width
+        );
+    }
+    let counts: Vec<String> = BulkUserAction::ALL
+        .iter()
+        .filter_map(|action| {
+            let count = tasks.iter().filter(|task| task.action == *action).count();
+            (count > 0).then(|| format!("{} to {}", count, action.label()))
+        })
+        .collect();
+    println!("{}", counts.join(", "));
+}
+
+pub fn user_delete(
+    config: &Config,
+    discourse_name: &str,
+    username: &str,
+    options: DeleteUserOptions,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    let mut extras = Vec::new();
+    if options.delete_posts {
+        extras.push("deleting their posts");
+    }
+    if options.block_email {
+        extras.push("blocking their email");
+    }
+    if options.block_ip {
+        extras.push("blocking their IP address");
+    }
+    if options.block_urls {
+        extras.push("blocking the URLs they posted");
+    }
+    let extras = if extras.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", extras.join(", "))
+    };
+    if dry_run {
+        println!(
+            "[dry-run] {}: would delete {}{}",
+            discourse.name, username, extras
+        );
+        return Ok(());
+    }
+
+    let detail = client.fetch_user_detail(username)?;
+    let action = format!(
+        "permanently delete {} (id:{}) from {}{}",
+        detail.username, detail.id, discourse.name, extras
+    );
+    if !assume_yes && !confirm_typed(&action, &detail.username)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    client.delete_user(detail.id, &options)?;
+    println!("Deleted {} (id:{})", detail.username, detail.id);
+    Ok(())
+}
+
+pub fn user_anonymize(
+    config: &Config,
+    discourse_name: &str,
+    username: &str,
+    assume_yes: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let discourse = select_discourse(config, Some(discourse_name))?;
+    ensure_api_credentials(discourse)?;
+    let client = DiscourseClient::new(discourse)?;
+
+    if dry_run {
+        println!("[dry-run] {}: would anonymize {}", discourse.name, username);
+        return Ok(());
+    }
+
+    let detail = client.fetch_user_detail(username)?;
+    let action = format!(
+        "permanently anonymize {} (id:{}) on {}",
+        detail.username, detail.id, discourse.name
+    );
+    if !assume_yes && !confirm_typed(&action, &detail.username)? {
+        println!("Aborted.");
+        return Ok(());
+    }
+    let anonymous = client.anonymize_user(detail.id)?;
+    println!(
+        "Anonymized {} (id:{}); now {}",
+        detail.username, detail.id, anonymous
+    );
+    Ok(())
+}
+
 #[derive(Clone, Copy)]
 pub enum Role {
     Admin,
@@ -654,6 +1343,138 @@ mod tests {
         );
     }
 
+    fn summary(
+        username: &str,
+        email: &str,
+        trust_level: u64,
+        last_seen: Option<&str>,
+    ) -> UserSummary {
+        UserSummary {
+            id: 1,
+            username: username.to_string(),
+            name: Some("A | B".to_string()),
+            email: Some(email.to_string()),
+            trust_level: Some(trust_level),
+            admin: None,
+            moderator: None,
+            suspended: None,
+            silenced: None,
+            last_seen_at: last_seen.map(str::to_string),
+            created_at: Some("2026-03-01T09:30:00.000Z".to_string()),
+            post_count: Some(12),
+        }
+    }
+
+    #[test]
+    fn user_filter_requires_every_criterion() {
+        let filter = UserFilter {
+            trust_levels: vec![0, 1],
+            last_seen_before: Some(parse_since_cutoff("2026-01-01").unwrap()),
+            created_after: Some(parse_since_cutoff("2026-02-01").unwrap()),
+            email_domains: vec!["example.com".to_string()],
+            members: None,
+        };
+        assert!(filter.matches(&summary("alice", "alice@Example.com", 1, None)));
+        assert!(filter.matches(&summary(
+            "bob",
+            "bob@example.com",
+            0,
+            Some("2025-12-31T23:00:00Z")
+        )));
+        assert!(!filter.matches(&summary("carol", "carol@example.com", 2, None)));
+        assert!(!filter.matches(&summary(
+            "dave",
+            "dave@example.com",
+            1,
+            Some("2026-01-02T00:00:00Z")
+        )));
+        assert!(!filter.matches(&summary("erin", "erin@sub.example.com", 1, None)));
+
+        let members = UserFilter {
+            members: Some(HashSet::from(["alice".to_string()])),
+            ..Default::default()
+        };
+        assert!(members.matches(&summary("Alice", "a@x.org", 3, None)));
+        assert!(!members.matches(&summary("bob", "b@x.org", 3, None)));
+    }
+
+    #[test]
+    fn user_table_shows_selected_columns() {
+        let users = vec![summary("alice", "alice@example.com", 2, None)];
+        let columns = [
+            UserColumn::Username,
+            UserColumn::Name,
+            UserColumn::PostCount,
+        ];
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        write_users_csv(&users, &columns, &mut writer).unwrap();
+        let csv = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(csv, "username,name,post_count\nalice,A | B,12\n");
+        assert_eq!(
+            render_users_markdown(&users, &columns),
+            "| username | name | post_count |\n| --- | --- | --- |\n| alice | A \\| B | 12 |\n"
+        );
+    }
+
+    #[test]
+    fn bulk_file_skips_header_and_comments() {
+        let raw = "user,action,until,reason\n# spam wave\nspammer1, suspend, 2026-12-31, spam\n\n\
+                   bob@example.com,delete\n";
+        let rows = parse_bulk_file(raw).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                BulkRow {
+                    line: 3,
+                    login: "spammer1".to_string(),
+                    action: "suspend".to_string(),
+                    until: "2026-12-31".to_string(),
+                    reason: "spam".to_string(),
+                },
+                BulkRow {
+                    line: 5,
+                    login: "bob@example.com".to_string(),
+                    action: "delete".to_string(),
+                    until: String::new(),
+                    reason: String::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn bulk_until_depends_on_the_action() {
+        use BulkUserAction::*;
+        assert!(check_bulk_until(Suspend, "forever").is_ok());
+        assert!(check_bulk_until(Suspend, "2026-12-31T00:00:00Z").is_ok());
+        assert!(check_bulk_until(Silence, "2026-12-31").is_ok());
+        assert!(check_bulk_until(Silence, "forever").is_err());
+        assert!(check_bulk_until(Suspend, "next week").is_err());
+        assert!(check_bulk_until(Delete, "2026-12-31").is_err());
+        assert!(check_bulk_until(Delete, "").is_ok());
+        assert_eq!(BulkUserAction::parse("Deactivate"), Some(Deactivate));
+        assert_eq!(BulkUserAction::parse("ban"), None);
+    }
+
+    #[test]
+    fn bulk_report_keeps_only_done_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spam.report.csv");
+        assert!(load_bulk_report(&path).unwrap().is_empty());
+        fs::write(
+            &path,
+            "line,user,action,status,message\n\
+             3,spammer1,suspend,done,\n\
+             4,spammer2,suspend,failed,not found\n",
+        )
+        .unwrap();
+        let done = load_bulk_report(&path).unwrap();
+        assert_eq!(done.len(), 1);
+        let row = parse_bulk_file("Spammer1,SUSPEND\nspammer1,silence\n").unwrap();
+        assert!(done[0].matches(&row[0]));
+        assert!(!done[0].matches(&row[1]));
+    }
+
     #[test]
     fn activity_url_for_op_omits_post_number() {
         let a = UserAction {
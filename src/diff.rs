@@ -0,0 +1,467 @@
+/// Result of a three-way merge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    /// Merged text, with conflict markers around any conflicting regions.
+    pub text: String,
+    /// Number of conflicting regions written.
+    pub conflicts: usize,
+}
+
+const MARKER_LOCAL: &str = "<<<<<<<";
+const MARKER_SPLIT: &str = "=======";
+const MARKER_REMOTE: &str = ">>>>>>>";
+
+/// Split text into lines, keeping line endings so a merge can reassemble
+/// the input byte-for-byte.
+pub fn split_lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// Longest common subsequence of two line slices, as `(a_start, b_start,
+/// len)` runs in ascending order. Uses Myers' O(ND) algorithm after
+/// trimming the common prefix and suffix.
+pub fn matching_blocks(a: &[&str], b: &[&str]) -> Vec<(usize, usize, usize)> {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let inner_a = &a[prefix..a.len() - suffix];
+    let inner_b = &b[prefix..b.len() - suffix];
+
+    let mut pairs = Vec::new();
+    for i in 0..prefix {
+        pairs.push((i, i));
+    }
+    for (x, y) in myers_pairs(inner_a, inner_b) {
+        pairs.push((x + prefix, y + prefix));
+    }
+    for i in 0..suffix {
+        pairs.push((a.len() - suffix + i, b.len() - suffix + i));
+    }
+
+    let mut blocks: Vec<(usize, usize, usize)> = Vec::new();
+    for (x, y) in pairs {
+        match blocks.last_mut() {
+            Some((bx, by, len)) if *bx + *len == x && *by + *len == y => *len += 1,
+            _ => blocks.push((x, y, 1)),
+        }
+    }
+    blocks
+}
+
+/// Matched `(a_index, b_index)` line pairs along a shortest edit script.
+fn myers_pairs(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    let n = a.len() as isize;
+    let m = b.len() as isize;
+    if n == 0 || m == 0 {
+        return Vec::new();
+    }
+    let max = n + m;
+    let offset = max + 1;
+    let mut v = vec![0isize; (2 * max + 3) as usize];
+    // trace[d] holds V for diagonals -(d-1)..=(d-1) as it stood before
+    // step d; that is all backtracking needs, and keeps memory O(D^2).
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    let mut final_d = 0;
+
+    'outer: for d in 0..=max {
+        let lo = (offset - (d - 1).max(0)) as usize;
+        let hi = (offset + (d - 1).max(0)) as usize;
+        trace.push(if d == 0 {
+            Vec::new()
+        } else {
+            v[lo..=hi].to_vec()
+        });
+        let mut k = -d;
+        while k <= d {
+            let idx = (offset + k) as usize;
+            let mut x = if k == -d || (k != d && v[idx - 1] < v[idx + 1]) {
+                v[idx + 1]
+            } else {
+                v[idx - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx] = x;
+            if x >= n && y >= m {
+                final_d = d;
+                break 'outer;
+            }
+            k += 2;
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..=final_d).rev() {
+        let k = x - y;
+        let (prev_x, prev_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let saved = &trace[d as usize];
+            let at = |k: isize| saved[(k + d - 1) as usize];
+            let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+                k + 1
+            } else {
+                k - 1
+            };
+            let prev_x = at(prev_k);
+            (prev_x, prev_x - prev_k)
+        };
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            pairs.push((x as usize, y as usize));
+        }
+        x = prev_x;
+        y = prev_y;
+    }
+    pairs.reverse();
+    pairs
+}
+
+/// Three-way merge of `local` and `remote` against their common `base`.
+///
+/// Regions changed on only one side take that side's version; regions
+/// changed identically on both sides are kept once; anything else becomes a
+/// conflict wrapped in `<<<<<<<` / `=======` / `>>>>>>>` markers.
+pub fn merge3(
+    base: &str,
+    local: &str,
+    remote: &str,
+    local_label: &str,
+    remote_label: &str,
+) -> MergeResult {
+    let base_lines = split_lines(base);
+    let a = split_lines(local);
+    let b = split_lines(remote);
+
+    let mut out = String::new();
+    let mut conflicts = 0;
+    let (mut iz, mut ia, mut ib) = (0, 0, 0);
+    for (zmatch, zend, amatch, aend, bmatch, bend) in sync_regions(&base_lines, &a, &b) {
+        let a_region = &a[ia..amatch];
+        let b_region = &b[ib..bmatch];
+        let z_region = &base_lines[iz..zmatch];
+        if !a_region.is_empty() || !b_region.is_empty() {
+            if a_region == b_region {
+                push_lines(&mut out, a_region);
+            } else if a_region == z_region {
+                push_lines(&mut out, b_region);
+            } else if b_region == z_region {
+                push_lines(&mut out, a_region);
+            } else {
+                conflicts += 1;
+                out.push_str(&format!("{} {}\n", MARKER_LOCAL, local_label));
+                push_terminated(&mut out, a_region);
+                out.push_str(MARKER_SPLIT);
+                out.push('\n');
+                push_terminated(&mut out, b_region);
+                out.push_str(&format!("{} {}\n", MARKER_REMOTE, remote_label));
+            }
+        }
+        push_lines(&mut out, &base_lines[zmatch..zend]);
+        iz = zend;
+        ia = aend;
+        ib = bend;
+    }
+    MergeResult {
+        text: out,
+        conflicts,
+    }
+}
+
+/// True when `text` still contains an unresolved conflict block written by
+/// `merge3`. A lone `=======` line is a Markdown heading underline, so both
+/// outer markers must be present.
+pub fn has_conflict_markers(text: &str) -> bool {
+    let mut open = false;
+    for line in text.lines() {
+        if line.starts_with(MARKER_LOCAL) {
+            open = true;
+        } else if open && line.starts_with(MARKER_REMOTE) {
+            return true;
+        }
+    }
+    false
+}
+
+/// One line of a unified diff hunk, without its line ending.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine<'a> {
+    Context(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// A unified diff hunk. Starts are 0-based line indexes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk<'a> {
+    pub old_start: usize,
+    pub old_len: usize,
+    pub new_start: usize,
+    pub new_len: usize,
+    pub lines: Vec<DiffLine<'a>>,
+}
+
+impl Hunk<'_> {
+    /// The `@@ -a,b +c,d @@` header line.
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{} +{} @@",
+            hunk_range(self.old_start, self.old_len),
+            hunk_range(self.new_start, self.new_len)
+        )
+    }
+}
+
+fn hunk_range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, len),
+    }
+}
+
+/// Line-by-line edits turning `old` into `new`, grouped into hunks with
+/// `context` unchanged lines around each change.
+pub fn unified_hunks<'a>(old: &'a str, new: &'a str, context: usize) -> Vec<Hunk<'a>> {
+    let a = split_lines(old);
+    let b = split_lines(new);
+    // Every line as (line, old index, new index), in output order.
+    let mut ops: Vec<(DiffLine<'a>, usize, usize)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let mut blocks = matching_blocks(&a, &b);
+    blocks.push((a.len(), b.len(), 0));
+    for (bi, bj, len) in blocks {
+        while i < bi {
+            ops.push((DiffLine::Removed(strip_eol(a[i])), i, j));
+            i += 1;
+        }
+        while j < bj {
+            ops.push((DiffLine::Added(strip_eol(b[j])), i, j));
+            j += 1;
+        }
+        for _ in 0..len {
+            ops.push((DiffLine::Context(strip_eol(a[i])), i, j));
+            i += 1;
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (line, _, _))| !matches!(line, DiffLine::Context(_)))
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut hunks = Vec::new();
+    let mut idx = 0;
+    while idx < changes.len() {
+        let first = changes[idx];
+        let mut last = first;
+        while idx + 1 < changes.len() && changes[idx + 1] - last <= 2 * context + 1 {
+            idx += 1;
+            last = changes[idx];
+        }
+        idx += 1;
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(ops.len());
+        let slice = &ops[start..end];
+        let (_, old_start, new_start) = slice[0];
+        let old_len = slice
+            .iter()
+            .filter(|(line, _, _)| !matches!(line, DiffLine::Added(_)))
+            .count();
+        let new_len = slice
+            .iter()
+            .filter(|(line, _, _)| !matches!(line, DiffLine::Removed(_)))
+            .count();
+        hunks.push(Hunk {
+            old_start,
+            old_len,
+            new_start,
+            new_len,
+            lines: slice.iter().map(|(line, _, _)| line.clone()).collect(),
+        });
+    }
+    hunks
+}
+
+/// Lines added and removed across `hunks`.
+pub fn diff_stat(hunks: &[Hunk]) -> (usize, usize) {
+    let mut added = 0;
+    let mut removed = 0;
+    for line in hunks.iter().flat_map(|hunk| &hunk.lines) {
+        match line {
+            DiffLine::Added(_) => added += 1,
+            DiffLine::Removed(_) => removed += 1,
+            DiffLine::Context(_) => {}
+        }
+    }
+    (added, removed)
+}
+
+fn strip_eol(line: &str) -> &str {
+    line.strip_suffix('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .unwrap_or(line)
+}
+
+/// Base ranges that are unchanged on both sides, with the matching ranges
+/// in `a` and `b`, ending with an empty sentinel region at the end of each
+/// input.
+fn sync_regions(
+    base: &[&str],
+    a: &[&str],
+    b: &[&str],
+) -> Vec<(usize, usize, usize, usize, usize, usize)> {
+    let a_blocks = matching_blocks(base, a);
+    let b_blocks = matching_blocks(base, b);
+    let mut regions = Vec::new();
+    let (mut ia, mut ib) = (0, 0);
+    while ia < a_blocks.len() && ib < b_blocks.len() {
+        let (abase, amatch, alen) = a_blocks[ia];
+        let (bbase, bmatch, blen) = b_blocks[ib];
+        let start = abase.max(bbase);
+        let end = (abase + alen).min(bbase + blen);
+        if start < end {
+            let asub = amatch + (start - abase);
+            let bsub = bmatch + (start - bbase);
+            let len = end - start;
+            regions.push((start, end, asub, asub + len, bsub, bsub + len));
+        }
+        if abase + alen < bbase + blen {
+            ia += 1;
+        } else {
+            ib += 1;
+        }
+    }
+    regions.push((base.len(), base.len(), a.len(), a.len(), b.len(), b.len()));
+    regions
+}
+
+fn push_lines(out: &mut String, lines: &[&str]) {
+    for line in lines {
+        out.push_str(line);
+    }
+}
+
+/// Push lines, making sure the last one ends with a newline so a following
+/// marker starts on its own line.
+fn push_terminated(out: &mut String, lines: &[&str]) {
+    push_lines(out, lines);
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_blocks_finds_common_runs() {
+        let a = ["a\n", "b\n", "c\n", "d\n"];
+        let b = ["a\n", "x\n", "c\n", "d\n"];
+        assert_eq!(matching_blocks(&a, &b), vec![(0, 0, 1), (2, 2, 2)]);
+    }
+
+    #[test]
+    fn matching_blocks_handles_insertions_and_deletions() {
+        let a = ["a\n", "b\n", "c\n"];
+        let b = ["z\n", "a\n", "c\n", "y\n"];
+        assert_eq!(matching_blocks(&a, &b), vec![(0, 1, 1), (2, 2, 1)]);
+        assert!(matching_blocks(&a, &[]).is_empty());
+    }
+
+    #[test]
+    fn merge_takes_non_overlapping_changes_from_both_sides() {
+        let base = "one\ntwo\nthree\nfour\n";
+        let local = "ONE\ntwo\nthree\nfour\n";
+        let remote = "one\ntwo\nthree\nFOUR\n";
+        let merged = merge3(base, local, remote, "local", "remote");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "ONE\ntwo\nthree\nFOUR\n");
+    }
+
+    #[test]
+    fn merge_keeps_identical_changes_once() {
+        let merged = merge3("a\nb\n", "a\nB\n", "a\nB\n", "local", "remote");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "a\nB\n");
+    }
+
+    #[test]
+    fn merge_applies_one_sided_deletion() {
+        let merged = merge3("a\nb\nc\n", "a\nc\n", "a\nb\nc\nd\n", "local", "remote");
+        assert_eq!(merged.conflicts, 0);
+        assert_eq!(merged.text, "a\nc\nd\n");
+    }
+
+    #[test]
+    fn merge_marks_conflicting_edits() {
+        let merged = merge3(
+            "a\nb\nc\n",
+            "a\nlocal\nc\n",
+            "a\nremote\nc\n",
+            "local",
+            "remote",
+        );
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< local\nlocal\n=======\nremote\n>>>>>>> remote\nc\n"
+        );
+        assert!(has_conflict_markers(&merged.text));
+    }
+
+    #[test]
+    fn merge_terminates_unterminated_conflict_lines() {
+        let merged = merge3("a", "b", "c", "local", "remote");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "<<<<<<< local\nb\n=======\nc\n>>>>>>> remote\n"
+        );
+    }
+
+    #[test]
+    fn setext_heading_is_not_a_conflict_marker() {
+        assert!(!has_conflict_markers("Title\n=======\n\nbody\n"));
+    }
+
+    #[test]
+    fn unified_hunks_group_nearby_changes_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n";
+        let new = "1\ntwo\n3\n4\n5\n6\n7\n8\n9\n10\n11\n";
+        let hunks = unified_hunks(old, new, 1);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].header(), "@@ -1,3 +1,3 @@");
+        assert_eq!(
+            hunks[0].lines,
+            vec![
+                DiffLine::Context("1"),
+                DiffLine::Removed("2"),
+                DiffLine::Added("two"),
+                DiffLine::Context("3"),
+            ]
+        );
+        assert_eq!(hunks[1].header(), "@@ -10 +10,2 @@");
+        assert_eq!(diff_stat(&hunks), (2, 1));
+        assert_eq!(unified_hunks(old, new, 4).len(), 1);
+    }
+
+    #[test]
+    fn unified_hunks_empty_for_identical_text() {
+        assert!(unified_hunks("a\nb\n", "a\nb\n", 3).is_empty());
+        let added = unified_hunks("", "a\n", 3);
+        assert_eq!(added[0].header(), "@@ -0,0 +1 @@");
+    }
+}
@@ -0,0 +1,370 @@
+use crate::utils::read_markdown;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Identity block written at the top of pulled topic files.
+///
+/// Every field is optional so hand-written files can carry as little as a
+/// `title`. `tags` distinguishes "leave tags alone" (`None`) from "clear
+/// every tag" (`Some(vec![])`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrontMatter {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discourse: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub topic_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Category slug.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Remote `updated_at` of the first post at pull time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    /// Position in the topic, for per-post files written by `--all-posts`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_number: Option<u64>,
+    /// Username of the post's author, for per-post files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Number of posts in a whole-thread archive written by `--all-posts`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posts: Option<usize>,
+}
+
+impl FrontMatter {
+    /// The topic this file points at on `discourse_name`, if any. Files
+    /// pulled from a different Discourse don't identify a topic here.
+    pub fn topic_id_for(&self, discourse_name: &str) -> Option<u64> {
+        match self.discourse.as_deref() {
+            Some(name) if name != discourse_name => None,
+            _ => self.topic_id,
+        }
+    }
+
+    /// Refuse files that aren't a topic's opening post: whole-thread
+    /// archives and reply files written by `--all-posts`.
+    pub fn ensure_pushable(&self, path: &Path) -> Result<()> {
+        if let Some(posts) = self.posts {
+            return Err(anyhow!(
+                "{} is a {}-post thread archive and can't be pushed",
+                path.display(),
+                posts
+            ));
+        }
+        if let Some(number) = self.post_number.filter(|n| *n > 1) {
+            return Err(anyhow!(
+                "{} holds reply #{}, not the opening post, and can't be pushed",
+                path.display(),
+                number
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Keys `FrontMatter` reads; a YAML block needs at least one of them to
+/// count as front matter.
+const FRONT_MATTER_KEYS: &[&str] = &[
+    "discourse",
+    "topic_id",
+    "post_id",
+    "title",
+    "category",
+    "tags",
+    "updated_at",
+    "post_number",
+    "author",
+    "posts",
+];
+
+/// Split a leading `---` YAML block off a Markdown document.
+///
+/// Returns the parsed block (if any) and the remaining body. A leading `---`
+/// that isn't followed by a YAML mapping (e.g. a horizontal rule), or whose
+/// mapping has none of the front matter keys (e.g. another tool's header),
+/// is left in the body untouched.
+pub fn parse_front_matter(raw: &str) -> Result<(Option<FrontMatter>, String)> {
+    let mut lines = raw.split_inclusive('\n');
+    let Some(first) = lines.next() else {
+        return Ok((None, raw.to_string()));
+    };
+    if first.trim_end() != "---" {
+        return Ok((None, raw.to_string()));
+    }
+
+    let mut offset = first.len();
+    let yaml_start = offset;
+    let mut yaml_end = None;
+    for line in lines {
+        if matches!(line.trim_end(), "---" | "...") {
+            yaml_end = Some(offset);
+            offset += line.len();
+            break;
+        }
+        offset += line.len();
+    }
+    let Some(yaml_end) = yaml_end else {
+        return Ok((None, raw.to_string()));
+    };
+
+    let yaml = &raw[yaml_start..yaml_end];
+    let value: serde_yaml::Value = match serde_yaml::from_str(yaml) {
+        Ok(value) => value,
+        Err(_) => return Ok((None, raw.to_string())),
+    };
+    let known = value.as_mapping().is_some_and(|mapping| {
+        mapping
+            .keys()
+            .filter_map(serde_yaml::Value::as_str)
+            .any(|key| FRONT_MATTER_KEYS.contains(&key))
+    });
+    if !known {
+        return Ok((None, raw.to_string()));
+    }
+    let front_matter: FrontMatter =
+        serde_yaml::from_value(value).context("parsing front matter")?;
+
+    let rest = &raw[offset..];
+    let body = rest
+        .strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))
+        .unwrap_or(rest);
+    Ok((Some(front_matter), body.to_string()))
+}
+
+/// Prefix `body` with a YAML front matter block.
+pub fn render_front_matter(front_matter: &FrontMatter, body: &str) -> Result<String> {
+    let yaml = serde_yaml::to_string(front_matter).context("serialising front matter")?;
+    Ok(format!("---\n{}---\n\n{}", yaml, body))
+}
+
+/// Read a Markdown file and split off its front matter.
+pub fn read_topic_file(path: &Path) -> Result<(Option<FrontMatter>, String)> {
+    let raw = read_markdown(path)?;
+    parse_front_matter(&raw).with_context(|| format!("reading {}", path.display()))
+}
+
+/// Split `topic push`/`topic sync` positionals: `<topic-id> <local-path>`,
+/// or just `<local-path>` to take the topic from the front matter.
+pub fn topic_id_and_path(first: &str, second: Option<PathBuf>) -> Result<(Option<u64>, PathBuf)> {
+    match second {
+        Some(path) => {
+            let id = first.parse().map_err(|_| {
+                anyhow!(
+                    "invalid topic ID {:?}; expected <topic-id> <local-path>",
+                    first
+                )
+            })?;
+            Ok((Some(id), path))
+        }
+        None => Ok((None, PathBuf::from(first))),
+    }
+}
+
+/// Pick the topic a push should target: the explicit ID wins, then the
+/// front matter's `topic_id` (when it was pulled from this Discourse).
+pub fn resolve_push_topic_id(
+    explicit: Option<u64>,
+    front_matter: Option<&FrontMatter>,
+    discourse_name: &str,
+    path: &Path,
+) -> Result<u64> {
+    if let Some(front_matter) = front_matter {
+        front_matter.ensure_pushable(path)?;
+    }
+    if let Some(id) = explicit {
+        return Ok(id);
+    }
+    let Some(front_matter) = front_matter else {
+        return Err(anyhow!(
+            "no topic ID given and {} has no front matter; pass a topic ID",
+            path.display()
+        ));
+    };
+    if let Some(id) = front_matter.topic_id_for(discourse_name) {
+        return Ok(id);
+    }
+    match front_matter.discourse.as_deref() {
+        Some(other) if other != discourse_name && front_matter.topic_id.is_some() => Err(anyhow!(
+            "{} was pulled from {}; pass a topic ID to push it to {}",
+            path.display(),
+            other,
+            discourse_name
+        )),
+        _ => Err(anyhow!(
+            "no topic ID given and {} has no topic_id in its front matter",
+            path.display()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> FrontMatter {
+        FrontMatter {
+            discourse: Some("myforum".to_string()),
+            topic_id: Some(42),
+            post_id: Some(99),
+            title: Some("Hello: world".to_string()),
+            category: Some("docs".to_string()),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+            updated_at: Some("2026-01-01T00:00:00.000Z".to_string()),
+            ..FrontMatter::default()
+        }
+    }
+
+    #[test]
+    fn render_then_parse_roundtrips() {
+        let rendered = render_front_matter(&sample(), "# Body\n\ntext\n").unwrap();
+        assert!(rendered.starts_with("---\n"));
+        let (fm, body) = parse_front_matter(&rendered).unwrap();
+        assert_eq!(fm, Some(sample()));
+        assert_eq!(body, "# Body\n\ntext\n");
+    }
+
+    #[test]
+    fn parse_without_front_matter_returns_body_unchanged() {
+        let (fm, body) = parse_front_matter("# Title\n\nbody").unwrap();
+        assert!(fm.is_none());
+        assert_eq!(body, "# Title\n\nbody");
+    }
+
+    #[test]
+    fn parse_leaves_horizontal_rule_alone() {
+        let raw = "---\nJust a paragraph between rules.\n---\n\nmore";
+        let (fm, body) = parse_front_matter(raw).unwrap();
+        assert!(fm.is_none());
+        assert_eq!(body, raw);
+    }
+
+    #[test]
+    fn parse_unterminated_block_is_not_front_matter() {
+        let raw = "---\ntitle: x\nno closing fence";
+        let (fm, body) = parse_front_matter(raw).unwrap();
+        assert!(fm.is_none());
+        assert_eq!(body, raw);
+    }
+
+    #[test]
+    fn parse_handles_crlf() {
+        let raw = "---\r\ntopic_id: 7\r\n---\r\n\r\nbody\r\n";
+        let (fm, body) = parse_front_matter(raw).unwrap();
+        assert_eq!(fm.unwrap().topic_id, Some(7));
+        assert_eq!(body, "body\r\n");
+    }
+
+    #[test]
+    fn parse_ignores_unknown_keys() {
+        let (fm, _) = parse_front_matter("---\ntitle: T\nauthor: me\n---\nbody").unwrap();
+        assert_eq!(fm.unwrap().title.as_deref(), Some("T"));
+    }
+
+    #[test]
+    fn parse_leaves_blocks_without_known_keys_in_the_body() {
+        let raw = "---\nlayout: post\npermalink: /about/\n---\nbody\n";
+        let (fm, body) = parse_front_matter(raw).unwrap();
+        assert!(fm.is_none());
+        assert_eq!(body, raw);
+    }
+
+    #[test]
+    fn every_field_is_a_known_key() {
+        let fm = FrontMatter {
+            discourse: Some("f".to_string()),
+            topic_id: Some(1),
+            post_id: Some(2),
+            title: Some("T".to_string()),
+            category: Some("c".to_string()),
+            tags: Some(Vec::new()),
+            updated_at: Some("2026-01-01T00:00:00Z".to_string()),
+            post_number: Some(1),
+            author: Some("a".to_string()),
+            posts: Some(1),
+        };
+        let value = serde_yaml::to_value(&fm).unwrap();
+        let keys: Vec<&str> = value
+            .as_mapping()
+            .unwrap()
+            .keys()
+            .filter_map(serde_yaml::Value::as_str)
+            .collect();
+        assert_eq!(keys, FRONT_MATTER_KEYS);
+    }
+
+    #[test]
+    fn missing_tags_key_differs_from_empty_list() {
+        let (fm, _) = parse_front_matter("---\ntitle: T\n---\n").unwrap();
+        assert_eq!(fm.unwrap().tags, None);
+        let (fm, _) = parse_front_matter("---\ntags: []\n---\n").unwrap();
+        assert_eq!(fm.unwrap().tags, Some(vec![]));
+    }
+
+    #[test]
+    fn topic_id_for_ignores_other_discourses() {
+        let fm = sample();
+        assert_eq!(fm.topic_id_for("myforum"), Some(42));
+        assert_eq!(fm.topic_id_for("other"), None);
+        let anonymous = FrontMatter {
+            topic_id: Some(5),
+            ..FrontMatter::default()
+        };
+        assert_eq!(anonymous.topic_id_for("anything"), Some(5));
+    }
+
+    #[test]
+    fn topic_id_comes_first_or_from_front_matter() {
+        assert_eq!(
+            topic_id_and_path("42", Some(PathBuf::from("a.md"))).unwrap(),
+            (Some(42), PathBuf::from("a.md"))
+        );
+        assert_eq!(
+            topic_id_and_path("a.md", None).unwrap(),
+            (None, PathBuf::from("a.md"))
+        );
+        assert!(topic_id_and_path("a.md", Some(PathBuf::from("42"))).is_err());
+    }
+
+    #[test]
+    fn resolve_push_topic_id_prefers_explicit() {
+        let path = Path::new("x.md");
+        assert_eq!(
+            resolve_push_topic_id(Some(1), Some(&sample()), "myforum", path).unwrap(),
+            1
+        );
+        assert_eq!(
+            resolve_push_topic_id(None, Some(&sample()), "myforum", path).unwrap(),
+            42
+        );
+        let err = resolve_push_topic_id(None, Some(&sample()), "other", path).unwrap_err();
+        assert!(err.to_string().contains("pulled from myforum"));
+        assert!(resolve_push_topic_id(None, None, "myforum", path).is_err());
+    }
+
+    #[test]
+    fn thread_archives_and_replies_cannot_be_pushed() {
+        let path = Path::new("x.md");
+        let archive = FrontMatter {
+            posts: Some(12),
+            ..sample()
+        };
+        assert!(resolve_push_topic_id(Some(1), Some(&archive), "myforum", path).is_err());
+        let reply = FrontMatter {
+            post_number: Some(3),
+            ..sample()
+        };
+        assert!(resolve_push_topic_id(None, Some(&reply), "myforum", path).is_err());
+        let opening = FrontMatter {
+            post_number: Some(1),
+            ..sample()
+        };
+        assert!(resolve_push_topic_id(None, Some(&opening), "myforum", path).is_ok());
+    }
+}
@@ -2,4 +2,6 @@ pub mod api;
 pub mod cli;
 pub mod commands;
 pub mod config;
+pub mod diff;
+pub mod frontmatter;
 pub mod utils;
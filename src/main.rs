@@ -1,8 +1,10 @@
 use anyhow::{anyhow, Result};
 use clap::Parser;
+use dsc::api::DeleteUserOptions;
 use dsc::cli::*;
 use dsc::commands;
 use dsc::commands::analytics::SectionFilter;
+use dsc::commands::topic::TopicStatusChange;
 use dsc::commands::user::{ActivityFormat, Role};
 
 fn map_section(s: SectionArg) -> SectionFilter {
@@ -14,6 +16,7 @@ fn map_section(s: SectionArg) -> SectionFilter {
     }
 }
 use dsc::config::{load_config, resolve_default_config_path, save_config};
+use dsc::frontmatter::topic_id_and_path;
 
 fn map_role(role: RoleArg) -> Role {
     match role {
@@ -116,20 +119,44 @@ fn main() -> Result<()> {
                 discourse,
                 topic_id,
                 local_path,
-            } => commands::topic::topic_pull(&config, &discourse, topic_id, local_path.as_deref()),
+                all_posts,
+                split,
+                download_uploads,
+            } => commands::topic::topic_pull(
+                &config,
+                &discourse,
+                topic_id,
+                local_path.as_deref(),
+                all_posts,
+                split,
+                download_uploads,
+            ),
 
             TopicCommand::Push {
                 discourse,
+                topic_or_path,
                 local_path,
-                topic_id,
-            } => commands::topic::topic_push(&config, &discourse, topic_id, &local_path, dry_run),
+            } => {
+                let (topic_id, local_path) = topic_id_and_path(&topic_or_path, local_path)?;
+                commands::topic::topic_push(&config, &discourse, topic_id, &local_path, dry_run)
+            }
 
             TopicCommand::Sync {
                 discourse,
-                topic_id,
+                topic_or_path,
                 local_path,
                 yes,
-            } => commands::topic::topic_sync(&config, &discourse, topic_id, &local_path, yes),
+            } => {
+                let (topic_id, local_path) = topic_id_and_path(&topic_or_path, local_path)?;
+                commands::topic::topic_sync(&config, &discourse, topic_id, &local_path, yes)
+            }
+
+            TopicCommand::Diff {
+                discourse,
+                local_path,
+                post,
+                stat,
+            } => commands::topic::topic_diff(&config, &discourse, &local_path, post, stat),
 
             TopicCommand::Reply {
                 discourse,
@@ -155,6 +182,187 @@ fn main() -> Result<()> {
                 local_path.as_deref(),
                 dry_run,
             ),
+
+            TopicCommand::Edit {
+                discourse,
+                topic_id,
+                title,
+                category,
+                tags,
+                featured_link,
+            } => commands::topic::topic_edit(
+                &config,
+                &discourse,
+                topic_id,
+                title.as_deref(),
+                category.as_deref(),
+                tags,
+                featured_link.as_deref(),
+                dry_run,
+            ),
+
+            TopicCommand::ChangeOwner {
+                discourse,
+                topic_id,
+                username,
+                post_ids,
+            } => commands::topic::topic_change_owner(
+                &config,
+                &discourse,
+                topic_id,
+                &username,
+                &post_ids,
+                dry_run,
+            ),
+
+            TopicCommand::Copy {
+                discourse,
+                topic_id,
+                target,
+                category,
+            } => commands::topic::topic_copy(
+                &config,
+                &discourse,
+                topic_id,
+                target.as_deref(),
+                &category,
+                dry_run,
+            ),
+
+            TopicCommand::Bulk {
+                discourse,
+                query,
+                action,
+                tags,
+                category,
+                yes,
+            } => commands::topic::topic_bulk(
+                &config,
+                &discourse,
+                &query,
+                action,
+                &tags,
+                category.as_deref(),
+                yes,
+                dry_run,
+            ),
+
+            TopicCommand::Close {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Close,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Open {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Open,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Pin {
+                discourse,
+                topic_ids,
+                global,
+                until,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Pin { global },
+                until.as_deref(),
+                dry_run,
+            ),
+
+            TopicCommand::Unpin {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Unpin,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Archive {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Archive,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Unarchive {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Unarchive,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Unlist {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Unlist,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Relist {
+                discourse,
+                topic_ids,
+            } => commands::topic::topic_set_status(
+                &config,
+                &discourse,
+                &topic_ids,
+                TopicStatusChange::Relist,
+                None,
+                dry_run,
+            ),
+
+            TopicCommand::Timer {
+                discourse,
+                topic_ids,
+                action,
+                time,
+                clear: _,
+                based_on_last_post,
+                category,
+            } => commands::topic::topic_timer(
+                &config,
+                &discourse,
+                &topic_ids,
+                action,
+                time.as_deref(),
+                based_on_last_post,
+                category.as_deref(),
+                dry_run,
+            ),
         },
 
         Commands::Category { command } => match command {
@@ -169,11 +377,15 @@ fn main() -> Result<()> {
                 discourse,
                 target,
                 category,
+                deep,
+                keep_name,
             } => commands::category::category_copy(
                 &config,
                 &discourse,
                 target.as_deref(),
                 &category,
+                deep,
+                keep_name,
                 dry_run,
             ),
 
@@ -181,18 +393,88 @@ fn main() -> Result<()> {
                 discourse,
                 category,
                 local_path,
+                include_subcategories,
+                recursive,
+                all_posts,
+                split,
+                download_uploads,
             } => commands::category::category_pull(
                 &config,
                 &discourse,
                 &category,
                 local_path.as_deref(),
+                include_subcategories,
+                recursive,
+                all_posts,
+                split,
+                download_uploads,
             ),
 
             CategoryCommand::Push {
                 discourse,
                 local_path,
                 category,
-            } => commands::category::category_push(&config, &discourse, &category, &local_path),
+                include_subcategories,
+                recursive,
+                removed,
+            } => commands::category::category_push(
+                &config,
+                &discourse,
+                &category,
+                &local_path,
+                include_subcategories,
+                recursive,
+                removed,
+                dry_run,
+            ),
+
+            CategoryCommand::Edit {
+                discourse,
+                category,
+                fields,
+            } => {
+                commands::category::category_edit(&config, &discourse, &category, &fields, dry_run)
+            }
+
+            CategoryCommand::Move {
+                discourse,
+                category,
+                parent,
+                top_level,
+                position,
+            } => commands::category::category_move(
+                &config,
+                &discourse,
+                &category,
+                parent.as_deref(),
+                top_level,
+                position,
+                dry_run,
+            ),
+
+            CategoryCommand::Reorder {
+                discourse,
+                categories,
+            } => commands::category::category_reorder(&config, &discourse, &categories, dry_run),
+
+            CategoryCommand::Delete {
+                discourse,
+                category,
+                force,
+                yes,
+            } => commands::category::category_delete(
+                &config, &discourse, &category, force, yes, dry_run,
+            ),
+
+            CategoryCommand::Export { discourse, file } => {
+                commands::category::category_export(&config, &discourse, file.as_deref())
+            }
+
+            CategoryCommand::Apply {
+                discourse,
+                file,
+                yes,
+            } => commands::category::category_apply(&config, &discourse, &file, yes, dry_run),
         },
 
         Commands::Group { command } => match command {
@@ -237,6 +519,61 @@ fn main() -> Result<()> {
                 notify,
                 dry_run,
             ),
+
+            GroupCommand::Create {
+                discourse,
+                name,
+                fields,
+            } => commands::group::group_create(&config, &discourse, &name, &fields, dry_run),
+
+            GroupCommand::Edit {
+                discourse,
+                group,
+                name,
+                fields,
+            } => commands::group::group_edit(
+                &config,
+                &discourse,
+                group,
+                name.as_deref(),
+                &fields,
+                dry_run,
+            ),
+
+            GroupCommand::Delete {
+                discourse,
+                group,
+                yes,
+            } => commands::group::group_delete(&config, &discourse, group, yes, dry_run),
+
+            GroupCommand::Apply {
+                discourse,
+                file,
+                yes,
+            } => commands::group::group_apply(&config, &discourse, &file, yes, dry_run),
+
+            GroupCommand::Sync {
+                discourse,
+                group,
+                local_path,
+                no_remove,
+                notify,
+                skip_unknown,
+                yes,
+            } => commands::group::group_sync(
+                &config,
+                &discourse,
+                group,
+                &local_path,
+                no_remove,
+                notify,
+                skip_unknown,
+                yes,
+                dry_run,
+            ),
+            GroupCommand::Matrix { tags, format } => {
+                commands::group::group_matrix(&config, tags.as_deref(), format)
+            }
         },
 
         Commands::Pm { command } => match command {
@@ -321,8 +658,13 @@ fn main() -> Result<()> {
                 discourse,
                 listing,
                 page,
+                all,
+                filters,
                 format,
-            } => commands::user::user_list(&config, &discourse, &listing, page, format),
+                columns,
+            } => commands::user::user_list(
+                &config, &discourse, &listing, page, all, &filters, format, &columns,
+            ),
             UserCommand::Info {
                 discourse,
                 username,
@@ -362,6 +704,47 @@ fn main() -> Result<()> {
                 discourse,
                 username,
             } => commands::user::user_unsilence(&config, &discourse, &username, dry_run),
+            UserCommand::Delete {
+                discourse,
+                username,
+                delete_posts,
+                block_email,
+                block_ip,
+                block_urls,
+                yes,
+            } => commands::user::user_delete(
+                &config,
+                &discourse,
+                &username,
+                DeleteUserOptions {
+                    delete_posts,
+                    block_email,
+                    block_ip,
+                    block_urls,
+                },
+                yes,
+                dry_run,
+            ),
+            UserCommand::Anonymize {
+                discourse,
+                username,
+                yes,
+            } => commands::user::user_anonymize(&config, &discourse, &username, yes, dry_run),
+            UserCommand::Bulk {
+                discourse,
+                local_path,
+                report,
+                delete_posts,
+                yes,
+            } => commands::user::user_bulk(
+                &config,
+                &discourse,
+                &local_path,
+                report.as_deref(),
+                delete_posts,
+                yes,
+                dry_run,
+            ),
             UserCommand::Promote {
                 discourse,
                 username,
@@ -651,6 +1034,82 @@ fn main() -> Result<()> {
                 post_id,
                 to_topic,
             } => commands::post::post_move(&config, &discourse, post_id, to_topic, dry_run),
+            PostCommand::Wiki {
+                discourse,
+                post_id,
+                state,
+            } => commands::post::post_wiki(
+                &config,
+                &discourse,
+                post_id,
+                state == Toggle::On,
+                dry_run,
+            ),
+            PostCommand::History {
+                discourse,
+                post_id,
+                format,
+            } => commands::post::post_history(&config, &discourse, post_id, format),
+            PostCommand::ShowRevision {
+                discourse,
+                post_id,
+                revision,
+            } => commands::post::post_show_revision(&config, &discourse, post_id, revision),
+            PostCommand::Revert {
+                discourse,
+                post_id,
+                revision,
+            } => commands::post::post_revert(&config, &discourse, post_id, revision, dry_run),
+            PostCommand::Replace {
+                discourse,
+                pattern,
+                replacement,
+                search,
+                category,
+                since,
+                user,
+                reason,
+                log,
+                yes,
+            } => commands::post::post_replace(
+                &config,
+                &discourse,
+                &pattern,
+                &replacement,
+                &commands::post::ReplaceScope {
+                    search: search.as_deref(),
+                    category: category.as_deref(),
+                    since: since.as_deref(),
+                    user: user.as_deref(),
+                },
+                reason.as_deref(),
+                log.as_deref(),
+                yes,
+                dry_run,
+            ),
+        },
+
+        Commands::Links { command } => match command {
+            LinksCommand::Check {
+                discourse,
+                category,
+                all,
+                concurrency,
+                per_host_rate,
+                timeout,
+                format,
+            } => commands::links::links_check(
+                &config,
+                &discourse,
+                category.as_deref(),
+                all,
+                &commands::links::ProbeLimits {
+                    concurrency,
+                    per_host_rate,
+                    timeout: std::time::Duration::from_secs(timeout),
+                },
+                format,
+            ),
         },
 
         Commands::Tag { command } => match command {
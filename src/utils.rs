@@ -108,6 +108,14 @@ pub fn color_discourse_label(label: &str, key: &str) -> String {
     format!("\x1b[1;{}m{}\x1b[0m", code, label)
 }
 
+/// Wrap `text` in the ANSI colour `code` when stdout allows colour.
+pub fn color_text(text: &str, code: u8) -> String {
+    if !color_allowed_for_stdout() {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
 /// Parse a `--since`-style value. Accepts either a relative duration
 /// (`7d`, `24h`, `30m`, `1w`, `90s`) or an ISO-8601 absolute timestamp
 /// (`2026-04-01`, `2026-04-01T12:00:00Z`). Returns the resulting cutoff
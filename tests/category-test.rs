@@ -145,7 +145,7 @@ fn category_push() {
     assert!(output.status.success(), "category push failed");
     let config = to_config(&test);
     let client = DiscourseClient::new(&config).expect("client");
-    let category = client.fetch_category(category_id).expect("category");
+    let category = client.fetch_category(category_id, false).expect("category");
     let found = category
         .topic_list
         .topics
@@ -153,3 +153,58 @@ fn category_push() {
         .any(|topic| topic.title.contains(&marker));
     assert!(found, "new category topic not found");
 }
+
+#[test]
+fn category_export_apply_and_edit_dry_run() {
+    let Some(test) = test_discourse() else {
+        return;
+    };
+    let Some(category_id) = test.test_category_id else {
+        return;
+    };
+    vprintln("e2e_category_export: export, then dry-run apply and edit");
+
+    let dir = TempDir::new().expect("tempdir");
+    let config_path = write_temp_config(
+        &dir,
+        &format!(
+            "[[discourse]]\nname = \"{}\"\nbaseurl = \"{}\"\napikey = \"{}\"\napi_username = \"{}\"\n",
+            test.name, test.baseurl, test.apikey, test.api_username
+        ),
+    );
+    let export_path = dir.path().join("categories.yaml");
+    let export_arg = export_path.to_string_lossy().to_string();
+    let output = run_dsc(
+        &["category", "export", &test.name, &export_arg],
+        &config_path,
+    );
+    assert!(output.status.success(), "category export failed");
+
+    let output = run_dsc(
+        &["-n", "category", "apply", &test.name, &export_arg],
+        &config_path,
+    );
+    assert!(output.status.success(), "category apply failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("0 to create, 0 to update"),
+        "applying a fresh export should change nothing: {}",
+        stdout
+    );
+
+    let output = run_dsc(
+        &[
+            "-n",
+            "category",
+            "edit",
+            &test.name,
+            &category_id.to_string(),
+            "--name",
+            "Renamed by dsc",
+        ],
+        &config_path,
+    );
+    assert!(output.status.success(), "category edit failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[dry-run]"), "edit was not a dry run");
+}